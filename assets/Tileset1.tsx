@@ -1,5 +1,5 @@
 <?xml version="1.0" encoding="UTF-8"?>
-<tileset version="1.10" tiledversion="1.11.0" name="Tileset1" tilewidth="32" tileheight="32" tilecount="7" columns="0">
+<tileset version="1.10" tiledversion="1.11.0" name="Tileset1" tilewidth="32" tileheight="32" tilecount="15" columns="0">
  <grid orientation="orthogonal" width="1" height="1"/>
  <tile id="0">
   <image source="tiles/tile0.png" width="32" height="32"/>
@@ -43,4 +43,76 @@
    <object id="2" name="collision" x="0" y="0" width="32" height="32"/>
   </objectgroup>
  </tile>
+ <tile id="7">
+  <properties>
+   <property name="restitution" type="float" value="0.8"/>
+  </properties>
+  <image source="tiles/tile7.png" width="32" height="32"/>
+  <objectgroup draworder="index" id="2">
+   <object id="1" name="collision" x="0" y="0" width="32" height="32"/>
+  </objectgroup>
+ </tile>
+ <tile id="8">
+  <properties>
+   <property name="friction" type="float" value="0.1"/>
+  </properties>
+  <image source="tiles/tile8.png" width="32" height="32"/>
+  <objectgroup draworder="index" id="2">
+   <object id="1" name="collision" x="0" y="0" width="32" height="32"/>
+  </objectgroup>
+ </tile>
+ <tile id="9">
+  <properties>
+   <property name="friction" type="float" value="1.5"/>
+  </properties>
+  <image source="tiles/tile9.png" width="32" height="32"/>
+  <objectgroup draworder="index" id="2">
+   <object id="1" name="collision" x="0" y="0" width="32" height="32"/>
+  </objectgroup>
+ </tile>
+ <tile id="10">
+  <properties>
+   <property name="hazard" type="bool" value="true"/>
+  </properties>
+  <image source="tiles/tile10.png" width="32" height="32"/>
+  <objectgroup draworder="index" id="2">
+   <object id="1" name="collision" x="0" y="0" width="32" height="32"/>
+  </objectgroup>
+ </tile>
+ <tile id="11">
+  <properties>
+   <property name="conveyor_speed" type="float" value="-120"/>
+  </properties>
+  <image source="tiles/tile11.png" width="32" height="32"/>
+  <objectgroup draworder="index" id="2">
+   <object id="1" name="collision" x="0" y="0" width="32" height="32"/>
+  </objectgroup>
+ </tile>
+ <tile id="12">
+  <properties>
+   <property name="door" value="red"/>
+  </properties>
+  <image source="tiles/tile12.png" width="32" height="32"/>
+  <objectgroup draworder="index" id="2">
+   <object id="1" name="collision" x="0" y="0" width="32" height="32"/>
+  </objectgroup>
+ </tile>
+ <tile id="13">
+  <properties>
+   <property name="door" value="green"/>
+  </properties>
+  <image source="tiles/tile13.png" width="32" height="32"/>
+  <objectgroup draworder="index" id="2">
+   <object id="1" name="collision" x="0" y="0" width="32" height="32"/>
+  </objectgroup>
+ </tile>
+ <tile id="14">
+  <properties>
+   <property name="door" value="blue"/>
+  </properties>
+  <image source="tiles/tile14.png" width="32" height="32"/>
+  <objectgroup draworder="index" id="2">
+   <object id="1" name="collision" x="0" y="0" width="32" height="32"/>
+  </objectgroup>
+ </tile>
 </tileset>
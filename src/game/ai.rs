@@ -0,0 +1,306 @@
+//! Duckling behaviour.
+//!
+//! Each free duckling has a [`DucklingBrain`] that decides what it wants to
+//! do and writes it to the duckling's [`MovementController`], exactly like
+//! player input does for the player. Ducklings following the player are
+//! moved by the conga line instead, so their brains are left alone.
+//!
+//! Some [`DucklingKind`]s behave differently: sleepy ducklings doze off
+//! whenever they are idle and the world is the right way up, and bouncy
+//! ducklings hop wherever they go.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use rand::Rng;
+
+use super::{
+    animation::AnimationParams,
+    conga::Following,
+    gravity::GravityFrame,
+    movement::MovementController,
+    simulation::{SimulationRng, TICK_SECS},
+    spawn::{
+        duckling::{Duckling, DucklingKind},
+        level::LevelMarker,
+        player::{IsOnGround, Player},
+    },
+};
+use crate::AppSet;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<DucklingBrain>();
+    app.add_systems(
+        FixedUpdate,
+        (
+            tick_duckling_brains.in_set(AppSet::TickTimers),
+            (update_duckling_brains, update_duckling_animation)
+                .chain()
+                .in_set(AppSet::RecordInput),
+        ),
+    );
+    app.add_systems(Update, update_duckling_direction.in_set(AppSet::Update));
+}
+
+/// Ducklings run away when the player gets this close, in pixels.
+const FLEE_RADIUS: f32 = 96.0;
+/// Fleeing ducklings calm down once the player is this far away.
+const CALM_RADIUS: f32 = 160.0;
+/// How long a duckling has to be in the air before it panics, in seconds.
+const PANIC_DELAY: f32 = 0.3;
+/// Movement input while wandering, as a fraction of full speed.
+const WANDER_SPEED: f32 = 0.4;
+/// The world counts as upside-down once the level's up direction points
+/// below this, i.e. when it is turned more than 135 degrees either way.
+const UPSIDE_DOWN_THRESHOLD: f32 = -0.7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+pub enum DucklingState {
+    /// Standing still for a while.
+    #[default]
+    Idle,
+    /// Walking slowly in one direction for a while.
+    Wander,
+    /// Running away from the player.
+    Flee,
+    /// Flapping about while falling.
+    Panic,
+    /// Fast asleep. Only sleepy ducklings do this.
+    Sleep,
+}
+
+impl DucklingState {
+    /// The name used for this state in the duckling's animation graph.
+    pub fn name(self) -> &'static str {
+        match self {
+            DucklingState::Idle => "idle",
+            DucklingState::Wander => "wander",
+            DucklingState::Flee => "flee",
+            DucklingState::Panic => "panic",
+            DucklingState::Sleep => "sleep",
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct DucklingBrain {
+    pub state: DucklingState,
+    /// How long to stay idle or keep wandering.
+    timer: Timer,
+    /// Time spent in the air, used to decide when to panic.
+    airborne: f32,
+    /// -1 for left, 1 for right.
+    direction: f32,
+}
+
+impl Default for DucklingBrain {
+    fn default() -> Self {
+        Self {
+            state: DucklingState::Idle,
+            timer: Timer::from_seconds(1.0, TimerMode::Once),
+            airborne: 0.0,
+            direction: 1.0,
+        }
+    }
+}
+
+impl DucklingBrain {
+    /// Sleepy ducklings start out asleep.
+    pub fn new(kind: DucklingKind) -> Self {
+        let state = match kind {
+            DucklingKind::Sleepy => DucklingState::Sleep,
+            _ => DucklingState::Idle,
+        };
+        Self { state, ..default() }
+    }
+
+    fn set_state(&mut self, state: DucklingState, rng: &mut impl Rng) {
+        if self.state == state {
+            return;
+        }
+        self.state = state;
+
+        match state {
+            DucklingState::Idle => {
+                self.timer = Timer::from_seconds(rng.gen_range(1.0..2.5), TimerMode::Once);
+            }
+            DucklingState::Wander => {
+                self.timer = Timer::from_seconds(rng.gen_range(1.0..3.0), TimerMode::Once);
+                self.direction = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+            }
+            DucklingState::Flee | DucklingState::Panic | DucklingState::Sleep => {}
+        }
+    }
+
+    /// Decide what to do next. `player_offset` is the player's position
+    /// relative to the duckling, if there is a player.
+    fn think(
+        &mut self,
+        kind: DucklingKind,
+        on_ground: bool,
+        player_offset: Option<Vec2>,
+        upside_down: bool,
+        rng: &mut impl Rng,
+    ) {
+        // Nothing wakes a sleeping duckling except turning the world over.
+        if self.state == DucklingState::Sleep {
+            if upside_down {
+                self.set_state(DucklingState::Idle, rng);
+            }
+            return;
+        }
+
+        if !on_ground && self.airborne >= PANIC_DELAY {
+            self.set_state(DucklingState::Panic, rng);
+            return;
+        }
+
+        let player_distance = player_offset.map_or(f32::INFINITY, Vec2::length);
+        match self.state {
+            DucklingState::Panic if on_ground => self.set_state(DucklingState::Idle, rng),
+            DucklingState::Panic => {}
+            DucklingState::Flee if player_distance > CALM_RADIUS => {
+                self.set_state(DucklingState::Idle, rng);
+            }
+            _ if player_distance < FLEE_RADIUS => self.set_state(DucklingState::Flee, rng),
+            DucklingState::Idle if self.timer.finished() => {
+                if kind == DucklingKind::Sleepy && !upside_down {
+                    self.set_state(DucklingState::Sleep, rng);
+                } else {
+                    self.set_state(DucklingState::Wander, rng);
+                }
+            }
+            DucklingState::Wander if self.timer.finished() => {
+                self.set_state(DucklingState::Idle, rng);
+            }
+            _ => {}
+        }
+
+        if let (DucklingState::Flee, Some(offset)) = (self.state, player_offset) {
+            // Run directly away, or pick a side if the player is right on top.
+            if offset.x != 0.0 {
+                self.direction = -offset.x.signum();
+            }
+        }
+    }
+
+    /// The movement input for the current state.
+    fn intent(&self, kind: DucklingKind) -> Vec2 {
+        // Bouncy ducklings jump whenever they are on the move.
+        let hop = if kind == DucklingKind::Bouncy {
+            1.0
+        } else {
+            0.0
+        };
+        match self.state {
+            DucklingState::Idle | DucklingState::Panic | DucklingState::Sleep => Vec2::ZERO,
+            DucklingState::Wander => Vec2::new(self.direction * WANDER_SPEED, hop),
+            DucklingState::Flee => Vec2::new(self.direction, hop),
+        }
+    }
+
+    pub fn is_asleep(&self) -> bool {
+        self.state == DucklingState::Sleep
+    }
+}
+
+fn tick_duckling_brains(mut query: Query<(&mut DucklingBrain, &IsOnGround), Without<Following>>) {
+    for (mut brain, is_on_ground) in &mut query {
+        brain.timer.tick(Duration::from_secs_f32(TICK_SECS));
+        if is_on_ground.is_on_ground {
+            brain.airborne = 0.0;
+        } else {
+            brain.airborne += TICK_SECS;
+        }
+    }
+}
+
+fn update_duckling_brains(
+    mut rng: ResMut<SimulationRng>,
+    player_query: Query<&Transform, With<Player>>,
+    level_query: Query<&Transform, With<LevelMarker>>,
+    mut duckling_query: Query<
+        (
+            &DucklingKind,
+            &Transform,
+            &IsOnGround,
+            &GravityFrame,
+            &mut DucklingBrain,
+            &mut MovementController,
+            Option<Ref<KinematicCharacterControllerOutput>>,
+        ),
+        (With<Duckling>, Without<Following>, Without<Player>),
+    >,
+) {
+    let player_pos = player_query
+        .get_single()
+        .ok()
+        .map(|transform| transform.translation.truncate());
+    let upside_down = level_query
+        .get_single()
+        .is_ok_and(|transform| (transform.rotation * Vec3::Y).y < UPSIDE_DOWN_THRESHOLD);
+
+    for (kind, transform, is_on_ground, frame, mut brain, mut controller, output) in
+        &mut duckling_query
+    {
+        let position = transform.translation.truncate();
+        brain.think(
+            *kind,
+            is_on_ground.is_on_ground,
+            player_pos.map(|p| frame.to_local(p - position)),
+            upside_down,
+            &mut *rng,
+        );
+
+        // Turn around on walking into a wall. The output only changes when
+        // physics has moved the duckling, so each collision is seen once.
+        if brain.state == DucklingState::Wander {
+            if let Some(output) = output.filter(|output| output.is_changed()) {
+                let desired = frame.to_local(output.desired_translation);
+                let effective = frame.to_local(output.effective_translation);
+                let blocked = desired.x.abs() > 0.1 && effective.x.abs() < 0.01;
+                if blocked {
+                    brain.direction = -brain.direction;
+                }
+            }
+        }
+
+        controller.0 = brain.intent(*kind);
+    }
+}
+
+/// Let the duckling's animation graph know what it is up to. Ducklings in
+/// the conga line are just following along.
+fn update_duckling_animation(
+    mut query: Query<(&DucklingBrain, Has<Following>, &mut AnimationParams), With<Duckling>>,
+) {
+    for (brain, following, mut params) in &mut query {
+        let state = if following {
+            "follow"
+        } else {
+            brain.state.name()
+        };
+        if params.state != state {
+            params.state = state;
+        }
+    }
+}
+
+/// Face the duckling sprite the way it is walking.
+fn update_duckling_direction(
+    duckling_query: Query<&MovementController, With<Duckling>>,
+    mut sprite_query: Query<(&Parent, &mut Sprite)>,
+) {
+    for (parent, mut sprite) in &mut sprite_query {
+        let Ok(controller) = duckling_query.get(parent.get()) else {
+            continue;
+        };
+        if controller.0.x < 0.0 {
+            sprite.flip_x = true;
+        } else if controller.0.x > 0.0 {
+            sprite.flip_x = false;
+        }
+    }
+}
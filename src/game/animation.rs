@@ -1,27 +1,45 @@
-//! Player sprite animation.
-//! This is based on multiple examples and may be very different for your game.
-//! - [Sprite flipping](https://github.com/bevyengine/bevy/blob/latest/examples/2d/sprite_flipping.rs)
-//! - [Sprite animation](https://github.com/bevyengine/bevy/blob/latest/examples/2d/sprite_animation.rs)
-//! - [Timers](https://github.com/bevyengine/bevy/blob/latest/examples/time/timers.rs)
+//! Sprite animation.
+//!
+//! Each animated character has a [`SpriteAnimationGraph`], loaded from an
+//! `.anim.ron` file in `assets/animations`. The graph lists the clips in the
+//! character's sprite sheet and the transitions between them. Transitions
+//! are checked in order every frame against the character's
+//! [`AnimationParams`], and the first one that matches is taken.
+//!
+//! A transition with no `from` list can be taken from any clip, but clips
+//! that don't loop get to finish first. Transitions that name a clip in
+//! their `from` list can cut it short.
 
-use std::time::Duration;
+use std::{collections::HashMap, time::Duration};
 
-use bevy::prelude::*;
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+};
+use serde::Deserialize;
+use thiserror::Error;
 
-use super::movement::MovementController;
+use super::{
+    conga::JoinCongaLine,
+    movement::{MovementController, WallContact},
+    spawn::player::{IsOnGround, Player, Velocity},
+};
 use crate::AppSet;
 
 pub(super) fn plugin(app: &mut App) {
-    // Animate and play sound effects based on controls.
-    app.register_type::<PlayerAnimation>();
+    app.init_asset::<SpriteAnimationGraph>();
+    app.init_asset_loader::<SpriteAnimationGraphLoader>();
+    app.register_type::<SpriteAnimator>();
+    app.register_type::<AnimationParams>();
+    app.observe(on_join_conga_line);
     app.add_systems(
         Update,
         (
             update_animation_timer.in_set(AppSet::TickTimers),
             (
-                update_animation_movement,
+                update_animation_params,
+                update_animation_clip,
                 update_animation_atlas,
-                animate_sprite,
             )
                 .chain()
                 .in_set(AppSet::Update),
@@ -29,167 +47,330 @@ pub(super) fn plugin(app: &mut App) {
     );
 }
 
-/// Update the sprite direction and animation state (idling/walking).
-fn update_animation_movement(
-    mut anim_query: Query<(&Parent, &mut PlayerAnimation)>,
-    player_query: Query<&MovementController>,
-) {
-    for (parent, mut animation) in &mut anim_query {
-        if let Ok(controller) = player_query.get(parent.get()) {
-            let animation_state = if controller.0 == Vec2::ZERO {
-                PlayerAnimationState::Idling
-            } else {
-                PlayerAnimationState::Walking
-            };
-            animation.update_state(animation_state);
-        }
-    }
+/// How long after a wall jump it still counts as wall jumping, in seconds.
+const WALL_JUMP_SECS: f32 = 0.24;
+
+/// A run of frames in a sprite sheet.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Clip {
+    /// Atlas index of the first frame.
+    pub first: usize,
+    /// Atlas index of the last frame, inclusive.
+    pub last: usize,
+    /// Frames per second.
+    pub fps: f32,
+    /// Whether the clip starts over after the last frame. Clips that don't
+    /// loop stay on their last frame.
+    #[serde(default = "default_looping")]
+    pub looping: bool,
+}
+
+fn default_looping() -> bool {
+    true
 }
 
-/// Update the animation timer.
-fn update_animation_timer(time: Res<Time>, mut query: Query<&mut PlayerAnimation>) {
-    for mut animation in &mut query {
-        animation.update_timer(time.delta());
+impl Clip {
+    fn len(&self) -> usize {
+        self.last - self.first + 1
     }
+
+    fn frame_time(&self) -> Duration {
+        Duration::from_secs_f32(1.0 / self.fps)
+    }
+}
+
+/// Something about a character that a transition can depend on.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum Condition {
+    OnGround,
+    InAir,
+    /// Trying to move sideways.
+    Moving,
+    Still,
+    Rising,
+    Falling,
+    WallSliding,
+    WallJumping,
+    /// A duckling just joined the conga line. True for both the duckling
+    /// and the player.
+    Collected,
+    /// The current clip doesn't loop and has played to the end.
+    Finished,
+    /// The character's behaviour is in this state, e.g. `State("sleep")`.
+    State(String),
 }
 
-/// Update the texture atlas to reflect changes in the animation.
-fn update_animation_atlas(mut query: Query<(&PlayerAnimation, &mut TextureAtlas)>) {
-    for (animation, mut atlas) in &mut query {
-        if animation.changed() {
-            atlas.index = animation.get_atlas_index();
+impl Condition {
+    fn holds(&self, params: &AnimationParams, finished: bool) -> bool {
+        match self {
+            Condition::OnGround => params.on_ground,
+            Condition::InAir => !params.on_ground,
+            Condition::Moving => params.moving,
+            Condition::Still => !params.moving,
+            Condition::Rising => params.velocity.y > 0.0,
+            Condition::Falling => params.velocity.y < 0.0,
+            Condition::WallSliding => params.wall_sliding,
+            Condition::WallJumping => params.wall_jumping,
+            Condition::Collected => params.collected,
+            Condition::Finished => finished,
+            Condition::State(state) => params.state == *state,
         }
     }
 }
 
-/// Component that tracks player's animation state.
-/// It is tightly bound to the texture atlas we use.
-#[derive(Component, Reflect, Clone)]
-#[reflect(Component)]
-pub struct PlayerAnimation {
-    timer: Timer,
-    frame: usize,
-    state: PlayerAnimationState,
+/// A switch from one clip to another.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Transition {
+    /// The clips this can be taken from. Empty means any clip.
+    #[serde(default)]
+    pub from: Vec<String>,
+    pub to: String,
+    /// Conditions that must all hold. Empty means always.
+    #[serde(default)]
+    pub when: Vec<Condition>,
 }
 
-#[derive(Reflect, PartialEq, Clone, Copy)]
-pub enum PlayerAnimationState {
-    Idling,
-    Walking,
+/// The clips a character has and when to play each one.
+#[derive(Asset, TypePath, Debug, Clone, Deserialize)]
+pub struct SpriteAnimationGraph {
+    /// The clip to start with.
+    pub initial: String,
+    pub clips: HashMap<String, Clip>,
+    pub transitions: Vec<Transition>,
 }
 
-impl PlayerAnimation {
-    /// The number of idle frames.
-    const IDLE_FRAMES: usize = 2;
-    /// The duration of each idle frame.
-    const IDLE_INTERVAL: Duration = Duration::from_millis(500);
+impl SpriteAnimationGraph {
+    /// The clip to switch to from `current`, if it should change.
+    fn next_clip(&self, current: &str, finished: bool, params: &AnimationParams) -> Option<&str> {
+        let interruptible = finished || self.clips.get(current).is_none_or(|clip| clip.looping);
+        let transition = self.transitions.iter().find(|transition| {
+            let from_here = if transition.from.is_empty() {
+                interruptible
+            } else {
+                transition.from.iter().any(|from| from == current)
+            };
+            from_here
+                && transition
+                    .when
+                    .iter()
+                    .all(|condition| condition.holds(params, finished))
+        })?;
+        (transition.to != current).then_some(transition.to.as_str())
+    }
 
-    fn idling() -> Self {
-        Self {
-            timer: Timer::new(Self::IDLE_INTERVAL, TimerMode::Repeating),
-            frame: 0,
-            state: PlayerAnimationState::Idling,
+    /// Check that every clip the graph refers to exists.
+    fn validate(&self) -> Result<(), SpriteAnimationGraphLoaderError> {
+        for (name, clip) in &self.clips {
+            if clip.last < clip.first || clip.fps <= 0.0 {
+                return Err(SpriteAnimationGraphLoaderError::BadClip(name.clone()));
+            }
         }
+        let names = std::iter::once(&self.initial).chain(
+            self.transitions
+                .iter()
+                .flat_map(|transition| transition.from.iter().chain([&transition.to])),
+        );
+        for name in names {
+            if !self.clips.contains_key(name) {
+                return Err(SpriteAnimationGraphLoaderError::UnknownClip(name.clone()));
+            }
+        }
+        Ok(())
     }
+}
 
-    /// The number of walking frames.
-    const WALKING_FRAMES: usize = 6;
-    /// The duration of each walking frame.
-    const WALKING_INTERVAL: Duration = Duration::from_millis(50);
+#[derive(Default)]
+struct SpriteAnimationGraphLoader;
 
-    fn walking() -> Self {
-        Self {
-            timer: Timer::new(Self::WALKING_INTERVAL, TimerMode::Repeating),
-            frame: 0,
-            state: PlayerAnimationState::Walking,
-        }
-    }
+#[non_exhaustive]
+#[derive(Debug, Error)]
+enum SpriteAnimationGraphLoaderError {
+    #[error("Could not load animation graph: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse animation graph: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("Animation graph refers to unknown clip: {0}")]
+    UnknownClip(String),
+    #[error("Animation clip has no frames or a bad frame rate: {0}")]
+    BadClip(String),
+}
 
-    pub fn new() -> Self {
-        Self::idling()
+impl AssetLoader for SpriteAnimationGraphLoader {
+    type Asset = SpriteAnimationGraph;
+    type Settings = ();
+    type Error = SpriteAnimationGraphLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let graph = ron::de::from_bytes::<SpriteAnimationGraph>(&bytes)?;
+        graph.validate()?;
+        Ok(graph)
     }
 
-    /// Update animation timers.
-    pub fn update_timer(&mut self, delta: Duration) {
-        self.timer.tick(delta);
-        if !self.timer.finished() {
-            return;
-        }
-        self.frame = (self.frame + 1)
-            % match self.state {
-                PlayerAnimationState::Idling => Self::IDLE_FRAMES,
-                PlayerAnimationState::Walking => Self::WALKING_FRAMES,
-            };
+    fn extensions(&self) -> &[&str] {
+        &["anim.ron"]
     }
+}
 
-    /// Update animation state if it changes.
-    pub fn update_state(&mut self, state: PlayerAnimationState) {
-        if self.state != state {
-            match state {
-                PlayerAnimationState::Idling => *self = Self::idling(),
-                PlayerAnimationState::Walking => *self = Self::walking(),
-            }
+/// What the animation graph knows about a character. This goes on the
+/// character, and its sprite gets a [`SpriteAnimator`].
+#[derive(Component, Debug, Clone, PartialEq, Default, Reflect)]
+#[reflect(Component)]
+pub struct AnimationParams {
+    pub on_ground: bool,
+    /// In the character's own frame, see [`super::gravity`].
+    pub velocity: Vec2,
+    pub moving: bool,
+    pub wall_sliding: bool,
+    pub wall_jumping: bool,
+    /// Set when a duckling joins the conga line, until the next clip update.
+    pub collected: bool,
+    /// The character's behaviour state, set by whatever drives it.
+    pub state: &'static str,
+}
+
+/// Plays a character's [`SpriteAnimationGraph`] on its sprite.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct SpriteAnimator {
+    graph: Handle<SpriteAnimationGraph>,
+    /// The clip being played. Empty until the graph has been looked at.
+    clip: String,
+    /// Frame within the clip.
+    frame: usize,
+    timer: Timer,
+    /// Whether a clip that doesn't loop has played to the end.
+    finished: bool,
+}
+
+impl SpriteAnimator {
+    pub fn new(graph: Handle<SpriteAnimationGraph>) -> Self {
+        Self {
+            graph,
+            clip: String::new(),
+            frame: 0,
+            timer: Timer::default(),
+            finished: false,
         }
     }
 
-    /// Whether animation changed this tick.
-    pub fn changed(&self) -> bool {
-        self.timer.finished()
+    /// Start playing a clip from the beginning.
+    fn play(&mut self, name: &str, clip: &Clip) {
+        self.clip = name.to_string();
+        self.frame = 0;
+        self.timer = Timer::new(clip.frame_time(), TimerMode::Repeating);
+        self.finished = false;
     }
 
-    /// Return sprite index in the atlas.
-    pub fn get_atlas_index(&self) -> usize {
-        match self.state {
-            PlayerAnimationState::Idling => self.frame,
-            PlayerAnimationState::Walking => 6 + self.frame,
+    fn tick(&mut self, delta: Duration, clip: &Clip) {
+        self.timer.tick(delta);
+        for _ in 0..self.timer.times_finished_this_tick() {
+            if self.frame + 1 < clip.len() {
+                self.frame += 1;
+            } else if clip.looping {
+                self.frame = 0;
+            } else {
+                self.finished = true;
+            }
         }
     }
 }
 
-#[derive(Component)]
-pub enum AnimationSequence {
-    Loop { first: usize, last: usize },
+/// Advance each sprite through its current clip.
+fn update_animation_timer(
+    time: Res<Time>,
+    graphs: Res<Assets<SpriteAnimationGraph>>,
+    mut query: Query<&mut SpriteAnimator>,
+) {
+    for mut animator in &mut query {
+        let Some(clip) = graphs
+            .get(&animator.graph)
+            .and_then(|graph| graph.clips.get(&animator.clip))
+        else {
+            continue;
+        };
+        animator.tick(time.delta(), clip);
+    }
 }
 
-impl AnimationSequence {
-    pub fn loop_forwards(first: usize, last: usize) -> Self {
-        AnimationSequence::Loop { first, last }
+/// Copy what the animation graphs need to know from each character.
+fn update_animation_params(
+    mut query: Query<(
+        &mut AnimationParams,
+        &Velocity,
+        &IsOnGround,
+        &MovementController,
+        Option<&WallContact>,
+    )>,
+) {
+    for (mut params, velocity, is_on_ground, controller, wall) in &mut query {
+        params.on_ground = is_on_ground.is_on_ground;
+        params.velocity = Vec2::new(velocity.x, velocity.y);
+        params.moving = controller.0.x != 0.0;
+        params.wall_sliding = wall.is_some_and(|wall| wall.sliding);
+        params.wall_jumping = wall.is_some_and(|wall| wall.since_wall_jump < WALL_JUMP_SECS);
     }
+}
 
-    // pub fn start_index(&self) -> usize {
-    //     match self {
-    //         AnimationSequence::Loop { first, .. } => *first,
-    //     }
-    // }
+/// Take the first transition that matches for each sprite.
+fn update_animation_clip(
+    graphs: Res<Assets<SpriteAnimationGraph>>,
+    mut anim_query: Query<(&Parent, &mut SpriteAnimator)>,
+    mut params_query: Query<&mut AnimationParams>,
+) {
+    for (parent, mut animator) in &mut anim_query {
+        let (Some(graph), Ok(mut params)) = (
+            graphs.get(&animator.graph),
+            params_query.get_mut(parent.get()),
+        ) else {
+            continue;
+        };
 
-    pub fn next_index(&mut self, atlas_index: usize) -> usize {
-        match self {
-            AnimationSequence::Loop { first, last } => {
-                if atlas_index == *last {
-                    *first
-                } else {
-                    atlas_index + 1
-                }
-            }
+        // Start over if the graph was reloaded without the current clip.
+        if !graph.clips.contains_key(&animator.clip) {
+            animator.play(&graph.initial, &graph.clips[&graph.initial]);
         }
+        if let Some(next) = graph.next_clip(&animator.clip, animator.finished, &params) {
+            animator.play(next, &graph.clips[next]);
+        }
+        params.collected = false;
     }
 }
 
-#[derive(Component, Deref, DerefMut)]
-pub struct AnimationTimer(pub Timer);
+/// Show the current frame of each sprite's clip.
+fn update_animation_atlas(
+    graphs: Res<Assets<SpriteAnimationGraph>>,
+    mut query: Query<(&SpriteAnimator, &mut TextureAtlas)>,
+) {
+    for (animator, mut atlas) in &mut query {
+        let Some(clip) = graphs
+            .get(&animator.graph)
+            .and_then(|graph| graph.clips.get(&animator.clip))
+        else {
+            continue;
+        };
+        let index = clip.first + animator.frame;
+        if atlas.index != index {
+            atlas.index = index;
+        }
+    }
+}
 
-pub fn animate_sprite(
-    time: Res<Time>,
-    mut query: Query<(
-        &mut AnimationSequence,
-        &mut AnimationTimer,
-        &mut TextureAtlas,
-    )>,
+fn on_join_conga_line(
+    trigger: Trigger<JoinCongaLine>,
+    player_query: Query<Entity, With<Player>>,
+    mut params_query: Query<&mut AnimationParams>,
 ) {
-    for (mut seq, mut timer, mut atlas) in &mut query {
-        timer.tick(time.delta());
-        if timer.just_finished() {
-            atlas.index = seq.next_index(atlas.index);
+    let duckling = trigger.event().0;
+    for entity in player_query.iter().chain([duckling]) {
+        if let Ok(mut params) = params_query.get_mut(entity) {
+            params.collected = true;
         }
     }
 }
@@ -4,6 +4,8 @@ use bevy::{
     utils::HashMap,
 };
 
+use super::animation::SpriteAnimationGraph;
+
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<HandleMap<ImageKey>>();
     app.init_resource::<HandleMap<ImageKey>>();
@@ -13,12 +15,24 @@ pub(super) fn plugin(app: &mut App) {
 
     app.register_type::<HandleMap<SoundtrackKey>>();
     app.init_resource::<HandleMap<SoundtrackKey>>();
+
+    app.register_type::<HandleMap<AnimationKey>>();
+    app.init_resource::<HandleMap<AnimationKey>>();
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Reflect)]
 pub enum ImageKey {
     Ducky,
     Duckling,
+    DucklingGolden,
+    DucklingSleepy,
+    DucklingBouncy,
+    Nest,
+    Spikes,
+    Platform,
+    Key,
+    Switch,
+    Fox,
 }
 
 impl AssetKey for ImageKey {
@@ -47,6 +61,87 @@ impl FromWorld for HandleMap<ImageKey> {
                     },
                 ),
             ),
+            (
+                ImageKey::DucklingGolden,
+                asset_server.load_with_settings(
+                    "images/duckling_golden.png",
+                    |settings: &mut ImageLoaderSettings| {
+                        settings.sampler = ImageSampler::nearest();
+                    },
+                ),
+            ),
+            (
+                ImageKey::DucklingSleepy,
+                asset_server.load_with_settings(
+                    "images/duckling_sleepy.png",
+                    |settings: &mut ImageLoaderSettings| {
+                        settings.sampler = ImageSampler::nearest();
+                    },
+                ),
+            ),
+            (
+                ImageKey::DucklingBouncy,
+                asset_server.load_with_settings(
+                    "images/duckling_bouncy.png",
+                    |settings: &mut ImageLoaderSettings| {
+                        settings.sampler = ImageSampler::nearest();
+                    },
+                ),
+            ),
+            (
+                ImageKey::Nest,
+                asset_server.load_with_settings(
+                    "images/nest.png",
+                    |settings: &mut ImageLoaderSettings| {
+                        settings.sampler = ImageSampler::nearest();
+                    },
+                ),
+            ),
+            (
+                ImageKey::Spikes,
+                asset_server.load_with_settings(
+                    "images/spikes.png",
+                    |settings: &mut ImageLoaderSettings| {
+                        settings.sampler = ImageSampler::nearest();
+                    },
+                ),
+            ),
+            (
+                ImageKey::Platform,
+                asset_server.load_with_settings(
+                    "images/platform.png",
+                    |settings: &mut ImageLoaderSettings| {
+                        settings.sampler = ImageSampler::nearest();
+                    },
+                ),
+            ),
+            (
+                ImageKey::Key,
+                asset_server.load_with_settings(
+                    "images/key.png",
+                    |settings: &mut ImageLoaderSettings| {
+                        settings.sampler = ImageSampler::nearest();
+                    },
+                ),
+            ),
+            (
+                ImageKey::Switch,
+                asset_server.load_with_settings(
+                    "images/switch.png",
+                    |settings: &mut ImageLoaderSettings| {
+                        settings.sampler = ImageSampler::nearest();
+                    },
+                ),
+            ),
+            (
+                ImageKey::Fox,
+                asset_server.load_with_settings(
+                    "images/fox.png",
+                    |settings: &mut ImageLoaderSettings| {
+                        settings.sampler = ImageSampler::nearest();
+                    },
+                ),
+            ),
         ]
         .into()
     }
@@ -58,6 +153,7 @@ pub enum SfxKey {
     ButtonPress,
     Jump,
     CollectDuckling,
+    Hurt,
 }
 
 impl AssetKey for SfxKey {
@@ -81,6 +177,7 @@ impl FromWorld for HandleMap<SfxKey> {
                 SfxKey::CollectDuckling,
                 asset_server.load("audio/sfx/collect.ogg"),
             ),
+            (SfxKey::Hurt, asset_server.load("audio/sfx/hurt.ogg")),
         ]
         .into()
     }
@@ -113,6 +210,38 @@ impl FromWorld for HandleMap<SoundtrackKey> {
     }
 }
 
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Reflect)]
+pub enum AnimationKey {
+    Ducky,
+    Duckling,
+    Fox,
+}
+
+impl AssetKey for AnimationKey {
+    type Asset = SpriteAnimationGraph;
+}
+
+impl FromWorld for HandleMap<AnimationKey> {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        [
+            (
+                AnimationKey::Ducky,
+                asset_server.load("animations/ducky.anim.ron"),
+            ),
+            (
+                AnimationKey::Duckling,
+                asset_server.load("animations/duckling.anim.ron"),
+            ),
+            (
+                AnimationKey::Fox,
+                asset_server.load("animations/fox.anim.ron"),
+            ),
+        ]
+        .into()
+    }
+}
+
 pub trait AssetKey: Sized {
     type Asset: Asset;
 }
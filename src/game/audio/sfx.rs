@@ -19,15 +19,20 @@ fn play_sfx(
         return;
     }
 
-    let sfx_key = match trigger.event() {
-        PlaySfx::Key(key) => *key,
-        PlaySfx::Jump => SfxKey::Jump,
-        PlaySfx::CollectDuckling => SfxKey::CollectDuckling,
+    let (sfx_key, speed) = match trigger.event() {
+        PlaySfx::Key(key) => (*key, 1.0),
+        PlaySfx::Jump => (SfxKey::Jump, 1.0),
+        PlaySfx::CollectDuckling => (SfxKey::CollectDuckling, 1.0),
+        // Picking up a key plays the duckling sound higher, so the two are
+        // easy to tell apart.
+        PlaySfx::CollectKey => (SfxKey::CollectDuckling, 1.5),
+        PlaySfx::Hurt => (SfxKey::Hurt, 1.0),
     };
     commands.spawn(AudioSourceBundle {
         source: sfx_handles[&sfx_key].clone_weak(),
         settings: PlaybackSettings {
             mode: PlaybackMode::Despawn,
+            speed,
             ..default()
         },
     });
@@ -40,4 +45,6 @@ pub enum PlaySfx {
     Key(SfxKey),
     Jump,
     CollectDuckling,
+    CollectKey,
+    Hurt,
 }
@@ -0,0 +1,91 @@
+//! The campaign manifest, which lists the levels in the order they are played.
+//!
+//! The manifest lives in `assets/campaign.ron` and is loaded with a custom
+//! [`AssetLoader`]. The [`Levels`] resource is rebuilt whenever the manifest
+//! changes, so with the `dev_native` feature enabled the level list can be
+//! edited while the game is running.
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+};
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::spawn::level::{CurrentLevel, Level, Levels};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<Campaign>();
+    app.init_asset_loader::<CampaignLoader>();
+    app.init_resource::<CampaignHandle>();
+    app.init_resource::<Levels>();
+    app.add_systems(Update, update_levels);
+}
+
+/// The list of levels that make up the game.
+#[derive(Asset, TypePath, Debug, Clone, Deserialize)]
+pub struct Campaign {
+    pub levels: Vec<Level>,
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct CampaignHandle(pub Handle<Campaign>);
+
+impl FromWorld for CampaignHandle {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        Self(asset_server.load("campaign.ron"))
+    }
+}
+
+#[derive(Default)]
+struct CampaignLoader;
+
+#[non_exhaustive]
+#[derive(Debug, Error)]
+enum CampaignLoaderError {
+    #[error("Could not load campaign: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse campaign: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+impl AssetLoader for CampaignLoader {
+    type Asset = Campaign;
+    type Settings = ();
+    type Error = CampaignLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes::<Campaign>(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["campaign.ron"]
+    }
+}
+
+/// Rebuild [`Levels`] when the campaign finishes loading or is hot reloaded.
+/// The current level is kept inside the campaign in case levels were removed.
+fn update_levels(
+    mut events: EventReader<AssetEvent<Campaign>>,
+    mut commands: Commands,
+    campaigns: Res<Assets<Campaign>>,
+    mut current_level: ResMut<CurrentLevel>,
+) {
+    for event in events.read() {
+        if let AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } = event {
+            if let Some(campaign) = campaigns.get(*id) {
+                log::info!("Campaign loaded with {} levels", campaign.levels.len());
+                commands.insert_resource(Levels::new(campaign.levels.clone()));
+                current_level.clamp(campaign.levels.len());
+            }
+        }
+    }
+}
@@ -0,0 +1,212 @@
+//! Collected ducklings follow the player in a line.
+//!
+//! The player leaves a trail of past positions, and each duckling in the
+//! line sits a fixed distance further back along it. Ducklings in the line
+//! are moved in `FixedUpdate` like everything else, so the usual
+//! [`PreviousPhysicalTranslation`](super::movement::PreviousPhysicalTranslation)
+//! interpolation smooths out their movement.
+//!
+//! Following ducklings keep a sensor collider so hazards can hit them. Trigger
+//! [`KnockLoose`] to drop ducklings out of the line again.
+
+use std::{collections::VecDeque, time::Duration};
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use rand::Rng;
+
+use super::{
+    score::{Score, UpdateScore},
+    simulation::{SimulationRng, TICK_SECS},
+    spawn::{
+        duckling::{duckling_controller, DucklingKind},
+        player::{Player, Velocity},
+    },
+};
+use crate::AppSet;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Following>();
+    app.observe(join_conga_line);
+    app.observe(knock_loose);
+    app.add_systems(
+        FixedUpdate,
+        (
+            tick_collect_cooldown.in_set(AppSet::TickTimers),
+            (record_trail, follow_trail).chain().in_set(AppSet::Update),
+        ),
+    );
+}
+
+/// Distance in pixels between points on the trail.
+const TRAIL_STEP: f32 = 4.0;
+/// Number of trail points between each duckling in the line.
+const TRAIL_POINTS_PER_DUCKLING: usize = 6;
+/// How long a duckling that was knocked loose can't be picked up again.
+const COLLECT_COOLDOWN_SECS: f32 = 1.0;
+/// Largest upwards speed given to a duckling that is knocked loose.
+const KNOCK_LOOSE_SPEED: f32 = 400.0;
+
+/// The player's trail and the ducklings following it.
+#[derive(Component, Debug, Clone, Default)]
+pub struct CongaLine {
+    /// Past positions of the player, most recent first.
+    trail: VecDeque<Vec2>,
+    /// The ducklings in the line, from front to back.
+    ducklings: Vec<Entity>,
+}
+
+impl CongaLine {
+    /// Start the trail again from `position`, e.g. after the player respawns.
+    /// The whole line jumps there with them.
+    pub fn reset_trail(&mut self, position: Vec2) {
+        self.trail.clear();
+        self.trail.push_front(position);
+    }
+
+    fn record(&mut self, position: Vec2) {
+        match self.trail.front() {
+            Some(last) if last.distance(position) < TRAIL_STEP => {}
+            _ => self.trail.push_front(position),
+        }
+        let needed = (self.ducklings.len() + 1) * TRAIL_POINTS_PER_DUCKLING + 1;
+        self.trail.truncate(needed);
+    }
+
+    /// Where the duckling at `index` in the line should be. Ducklings bunch
+    /// up at the end of the trail until it is long enough.
+    fn position(&self, index: usize) -> Option<Vec2> {
+        let point = (index + 1) * TRAIL_POINTS_PER_DUCKLING;
+        self.trail.get(point).or(self.trail.back()).copied()
+    }
+}
+
+/// Marks a duckling that is following the player.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Following;
+
+/// A duckling that was knocked loose recently and can't be collected yet.
+#[derive(Component, Debug)]
+pub struct CollectCooldown(Timer);
+
+/// Add a duckling to the back of the player's line.
+#[derive(Event, Debug)]
+pub struct JoinCongaLine(pub Entity);
+
+/// Knock a duckling out of the line, along with every duckling behind it.
+/// Pass the player's entity to knock the whole line loose.
+#[derive(Event, Debug)]
+pub struct KnockLoose(pub Entity);
+
+fn join_conga_line(
+    trigger: Trigger<JoinCongaLine>,
+    mut commands: Commands,
+    mut line_query: Query<&mut CongaLine, With<Player>>,
+) {
+    let duckling = trigger.event().0;
+    let Ok(mut line) = line_query.get_single_mut() else {
+        return;
+    };
+    if line.ducklings.contains(&duckling) {
+        return;
+    }
+
+    line.ducklings.push(duckling);
+    commands
+        .entity(duckling)
+        .remove::<KinematicCharacterController>()
+        .insert((
+            Following,
+            // Moved along the trail rather than by its own controller. The
+            // collider becomes a sensor so it doesn't get in the player's way,
+            // but hazards can still detect it.
+            Sensor,
+            ActiveEvents::COLLISION_EVENTS,
+            ActiveCollisionTypes::default() | ActiveCollisionTypes::KINEMATIC_STATIC,
+        ));
+}
+
+fn knock_loose(
+    trigger: Trigger<KnockLoose>,
+    mut commands: Commands,
+    mut score: ResMut<Score>,
+    mut rng: ResMut<SimulationRng>,
+    mut line_query: Query<(Entity, &mut CongaLine), With<Player>>,
+    kind_query: Query<&DucklingKind>,
+) {
+    let target = trigger.event().0;
+    let Ok((player, mut line)) = line_query.get_single_mut() else {
+        return;
+    };
+
+    let from = if target == player {
+        0
+    } else {
+        match line.ducklings.iter().position(|&entity| entity == target) {
+            Some(index) => index,
+            None => return,
+        }
+    };
+
+    let loose = line.ducklings.split_off(from);
+    if loose.is_empty() {
+        return;
+    }
+
+    for duckling in &loose {
+        // Send them flying. They take over their own movement again.
+        let velocity = Velocity {
+            x: 0.0,
+            y: rng.gen_range(0.5..1.0) * KNOCK_LOOSE_SPEED,
+        };
+        commands
+            .entity(*duckling)
+            .remove::<(Following, Sensor, ActiveEvents, ActiveCollisionTypes)>()
+            .insert((
+                duckling_controller(),
+                velocity,
+                CollectCooldown(Timer::from_seconds(COLLECT_COOLDOWN_SECS, TimerMode::Once)),
+            ));
+    }
+
+    // Losing ducklings loses their points too.
+    let points: u32 = kind_query.iter_many(&loose).map(|kind| kind.points()).sum();
+    score.ducklings_collected = score.ducklings_collected.saturating_sub(loose.len() as u32);
+    score.score = score.score.saturating_sub(points);
+    commands.trigger(UpdateScore);
+}
+
+fn record_trail(mut query: Query<(&Transform, &mut CongaLine), With<Player>>) {
+    for (transform, mut line) in &mut query {
+        line.record(transform.translation.truncate());
+    }
+}
+
+fn follow_trail(
+    line_query: Query<&CongaLine, With<Player>>,
+    mut duckling_query: Query<&mut Transform, (With<Following>, Without<Player>)>,
+) {
+    for line in &line_query {
+        for (index, &duckling) in line.ducklings.iter().enumerate() {
+            let (Some(position), Ok(mut transform)) =
+                (line.position(index), duckling_query.get_mut(duckling))
+            else {
+                continue;
+            };
+            transform.translation = position.extend(transform.translation.z);
+        }
+    }
+}
+
+fn tick_collect_cooldown(mut commands: Commands, mut query: Query<(Entity, &mut CollectCooldown)>) {
+    for (entity, mut cooldown) in &mut query {
+        if cooldown
+            .0
+            .tick(Duration::from_secs_f32(TICK_SECS))
+            .finished()
+        {
+            commands.entity(entity).remove::<CollectCooldown>();
+        }
+    }
+}
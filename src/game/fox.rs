@@ -0,0 +1,374 @@
+//! Fox behaviour.
+//!
+//! Foxes patrol back and forth until they spot a free duckling, then chase
+//! it down. A fox that catches a duckling eats it, and the duckling is gone
+//! for good, so the level's duckling total goes down. Ducklings following
+//! the player are safe. If the foxes eat every duckling, the level restarts.
+//!
+//! Foxes don't hurt the player, but they bump them away when they meet.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use rand::Rng;
+
+use super::{
+    animation::AnimationParams,
+    audio::sfx::PlaySfx,
+    conga::Following,
+    gravity::GravityFrame,
+    movement::{Knockback, MovementController},
+    score::{Score, UpdateScore},
+    simulation::{SimulationRng, TICK_SECS},
+    spawn::{
+        duckling::Duckling,
+        fox::Fox,
+        level::{EndLevel, LevelCompleted, LevelFinishPoint, RestartLevel},
+        player::{Player, Velocity},
+    },
+};
+use crate::AppSet;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<FoxBrain>();
+    app.observe(on_duckling_eaten);
+    app.add_systems(
+        FixedUpdate,
+        (
+            tick_fox_brains.in_set(AppSet::TickTimers),
+            (update_fox_brains, update_fox_animation)
+                .chain()
+                .in_set(AppSet::RecordInput),
+            (eat_ducklings, bump_player).in_set(AppSet::Update),
+        ),
+    );
+    app.add_systems(Update, update_fox_direction.in_set(AppSet::Update));
+}
+
+/// Foxes spot free ducklings this close, in pixels.
+const CHASE_RADIUS: f32 = 160.0;
+/// Foxes give up the chase once their duckling is this far away.
+const GIVE_UP_RADIUS: f32 = 240.0;
+/// Foxes jump after ducklings that are this much higher up than them.
+const JUMP_HEIGHT: f32 = 24.0;
+/// Movement input while patrolling, as a fraction of full speed.
+const PATROL_SPEED: f32 = 0.5;
+/// A fox eats a duckling once it gets this close.
+const EAT_DISTANCE: f32 = 28.0;
+/// How long a fox stops to eat, in seconds.
+const EAT_SECS: f32 = 1.5;
+/// A fox bumps the player once they are this close.
+const BUMP_DISTANCE: f32 = 30.0;
+/// How hard the player is pushed away from a fox, in pixels per second.
+const BUMP_SPEED: f32 = 350.0;
+/// Upwards speed the player is thrown with when bumped.
+const BUMP_UP_SPEED: f32 = 200.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+pub enum FoxState {
+    /// Walking back and forth.
+    #[default]
+    Patrol,
+    /// Running after a duckling.
+    Chase,
+    /// Eating a duckling it caught.
+    Eat,
+}
+
+impl FoxState {
+    /// The name used for this state in the fox's animation graph.
+    pub fn name(self) -> &'static str {
+        match self {
+            FoxState::Patrol => "patrol",
+            FoxState::Chase => "chase",
+            FoxState::Eat => "eat",
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct FoxBrain {
+    pub state: FoxState,
+    /// How long to keep patrolling in one direction, or to keep eating.
+    timer: Timer,
+    /// -1 for left, 1 for right.
+    direction: f32,
+    /// The duckling being chased.
+    target: Option<Entity>,
+}
+
+impl Default for FoxBrain {
+    fn default() -> Self {
+        Self {
+            state: FoxState::Patrol,
+            timer: Timer::from_seconds(2.0, TimerMode::Once),
+            direction: 1.0,
+            target: None,
+        }
+    }
+}
+
+impl FoxBrain {
+    fn set_state(&mut self, state: FoxState, rng: &mut impl Rng) {
+        if self.state == state {
+            return;
+        }
+        self.state = state;
+
+        match state {
+            FoxState::Patrol => {
+                let secs = rng.gen_range(2.0..4.0);
+                self.timer = Timer::from_seconds(secs, TimerMode::Once);
+                self.target = None;
+            }
+            FoxState::Eat => {
+                self.timer = Timer::from_seconds(EAT_SECS, TimerMode::Once);
+                self.target = None;
+            }
+            FoxState::Chase => {}
+        }
+    }
+
+    /// Decide what to do next. `target_offset` is where the duckling being
+    /// chased is relative to the fox, and `nearest` is the closest free
+    /// duckling and its offset.
+    fn think(
+        &mut self,
+        target_offset: Option<Vec2>,
+        nearest: Option<(Entity, Vec2)>,
+        rng: &mut impl Rng,
+    ) {
+        match self.state {
+            FoxState::Eat if self.timer.finished() => self.set_state(FoxState::Patrol, rng),
+            FoxState::Eat => {}
+            FoxState::Chase => match target_offset {
+                Some(offset) if offset.length() <= GIVE_UP_RADIUS => {
+                    if offset.x != 0.0 {
+                        self.direction = offset.x.signum();
+                    }
+                }
+                _ => self.set_state(FoxState::Patrol, rng),
+            },
+            FoxState::Patrol => match nearest {
+                Some((duckling, offset)) if offset.length() < CHASE_RADIUS => {
+                    self.set_state(FoxState::Chase, rng);
+                    self.target = Some(duckling);
+                }
+                _ if self.timer.finished() => {
+                    self.turn_around();
+                    self.timer.reset();
+                }
+                _ => {}
+            },
+        }
+    }
+
+    fn turn_around(&mut self) {
+        self.direction = -self.direction;
+    }
+
+    /// The movement input for the current state.
+    fn intent(&self, target_offset: Option<Vec2>) -> Vec2 {
+        match self.state {
+            FoxState::Patrol => Vec2::new(self.direction * PATROL_SPEED, 0.0),
+            FoxState::Chase => {
+                let jump = target_offset.is_some_and(|offset| offset.y > JUMP_HEIGHT);
+                Vec2::new(self.direction, if jump { 1.0 } else { 0.0 })
+            }
+            FoxState::Eat => Vec2::ZERO,
+        }
+    }
+}
+
+/// A fox caught a duckling.
+#[derive(Event, Debug)]
+pub struct DucklingEaten {
+    pub fox: Entity,
+    pub duckling: Entity,
+}
+
+fn tick_fox_brains(mut query: Query<&mut FoxBrain>) {
+    for mut brain in &mut query {
+        brain.timer.tick(Duration::from_secs_f32(TICK_SECS));
+    }
+}
+
+fn update_fox_brains(
+    mut rng: ResMut<SimulationRng>,
+    duckling_query: Query<(Entity, &Transform), (With<Duckling>, Without<Following>)>,
+    mut fox_query: Query<
+        (
+            &Transform,
+            &GravityFrame,
+            &mut FoxBrain,
+            &mut MovementController,
+            Option<Ref<KinematicCharacterControllerOutput>>,
+        ),
+        (With<Fox>, Without<Duckling>),
+    >,
+) {
+    for (transform, frame, mut brain, mut controller, output) in &mut fox_query {
+        let position = transform.translation.truncate();
+        let target_offset = brain
+            .target
+            .and_then(|target| duckling_query.get(target).ok())
+            .map(|(_, target)| frame.to_local(target.translation.truncate() - position));
+        let nearest = duckling_query
+            .iter()
+            .map(|(duckling, target)| {
+                let offset = frame.to_local(target.translation.truncate() - position);
+                (duckling, offset)
+            })
+            .min_by(|(_, a), (_, b)| a.length_squared().total_cmp(&b.length_squared()));
+        brain.think(target_offset, nearest, &mut *rng);
+
+        // Turn around on walking into a wall while patrolling, or jump it
+        // while chasing. The output only changes when physics has moved the
+        // fox, so each collision is seen once.
+        let mut intent = brain.intent(target_offset);
+        if let Some(output) = output.filter(|output| output.is_changed()) {
+            let desired = frame.to_local(output.desired_translation);
+            let effective = frame.to_local(output.effective_translation);
+            let blocked = desired.x.abs() > 0.1 && effective.x.abs() < 0.01;
+            match brain.state {
+                FoxState::Patrol if blocked => brain.turn_around(),
+                FoxState::Chase if blocked => intent.y = 1.0,
+                _ => {}
+            }
+        }
+
+        controller.0 = intent;
+    }
+}
+
+fn eat_ducklings(
+    mut commands: Commands,
+    fox_query: Query<(Entity, &GlobalTransform, &FoxBrain), With<Fox>>,
+    duckling_query: Query<(Entity, &GlobalTransform), (With<Duckling>, Without<Following>)>,
+) {
+    for (fox, fox_tf, brain) in &fox_query {
+        if brain.state == FoxState::Eat {
+            continue;
+        }
+        let caught = duckling_query.iter().find(|(_, duckling_tf)| {
+            fox_tf.translation().distance(duckling_tf.translation()) < EAT_DISTANCE
+        });
+        if let Some((duckling, _)) = caught {
+            commands.trigger(DucklingEaten { fox, duckling });
+        }
+    }
+}
+
+fn on_duckling_eaten(
+    trigger: Trigger<DucklingEaten>,
+    mut commands: Commands,
+    mut score: ResMut<Score>,
+    mut rng: ResMut<SimulationRng>,
+    duckling_query: Query<(), (With<Duckling>, Without<Following>)>,
+    mut fox_query: Query<&mut FoxBrain>,
+    mut exits: Query<&mut LevelFinishPoint>,
+) {
+    let DucklingEaten { fox, duckling } = *trigger.event();
+    if !duckling_query.contains(duckling) {
+        return;
+    }
+
+    log::info!("A fox ate a duckling");
+    commands.entity(duckling).despawn_recursive();
+    commands.trigger(PlaySfx::Hurt);
+    if let Ok(mut brain) = fox_query.get_mut(fox) {
+        brain.set_state(FoxState::Eat, &mut *rng);
+    }
+
+    // There's one less duckling to collect now, and exits can't ask for
+    // more ducklings than are left. They still want at least one, so the foxes
+    // can't finish a level for the player.
+    score.ducklings_total = score.ducklings_total.saturating_sub(1);
+    for mut exit in &mut exits {
+        exit.ducklings_required = exit.ducklings_required.min(score.ducklings_total.max(1));
+    }
+    commands.trigger(UpdateScore);
+
+    // With every duckling eaten there's nothing left to save, so the level
+    // starts again.
+    if score.ducklings_total == 0 {
+        log::info!("The foxes ate every duckling, restarting the level");
+        commands.trigger(RestartLevel);
+        return;
+    }
+
+    // Losing the last duckling that was still out there finishes the level,
+    // as long as the player saved at least one of them.
+    if score.ducklings_collected > 0
+        && score.ducklings_collected == score.ducklings_total
+        && exits.is_empty()
+    {
+        commands.trigger(LevelCompleted);
+        commands.trigger(EndLevel);
+    }
+}
+
+/// Push the player away from any fox they run into. A player who is still
+/// being pushed can't be bumped again.
+fn bump_player(
+    mut commands: Commands,
+    fox_query: Query<&GlobalTransform, With<Fox>>,
+    mut player_query: Query<
+        (Entity, &GlobalTransform, &GravityFrame, &mut Velocity),
+        (With<Player>, Without<Knockback>),
+    >,
+) {
+    let Ok((player, player_tf, frame, mut velocity)) = player_query.get_single_mut() else {
+        return;
+    };
+
+    for fox_tf in &fox_query {
+        let delta = (player_tf.translation() - fox_tf.translation()).truncate();
+        if delta.length() >= BUMP_DISTANCE {
+            continue;
+        }
+
+        // Push the player along the ground, away from the fox.
+        let side = frame.to_local(delta).x;
+        let direction = if side < 0.0 {
+            -frame.right()
+        } else {
+            frame.right()
+        };
+        commands
+            .entity(player)
+            .insert(Knockback(direction * BUMP_SPEED));
+        velocity.y = velocity.y.max(BUMP_UP_SPEED);
+        commands.trigger(PlaySfx::Hurt);
+        return;
+    }
+}
+
+/// Let the fox's animation graph know what it is up to.
+fn update_fox_animation(mut query: Query<(&FoxBrain, &mut AnimationParams)>) {
+    for (brain, mut params) in &mut query {
+        let state = brain.state.name();
+        if params.state != state {
+            params.state = state;
+        }
+    }
+}
+
+/// Face the fox sprite the way it is walking.
+fn update_fox_direction(
+    fox_query: Query<&MovementController, With<Fox>>,
+    mut sprite_query: Query<(&Parent, &mut Sprite)>,
+) {
+    for (parent, mut sprite) in &mut sprite_query {
+        let Ok(controller) = fox_query.get(parent.get()) else {
+            continue;
+        };
+        if controller.0.x < 0.0 {
+            sprite.flip_x = true;
+        } else if controller.0.x > 0.0 {
+            sprite.flip_x = false;
+        }
+    }
+}
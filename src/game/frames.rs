@@ -2,6 +2,7 @@ use bevy::prelude::*;
 
 use crate::AppSet;
 
+/// Simulation ticks since the level finished loading.
 #[derive(Default, Resource)]
 pub struct FrameCounter {
     pub count: u32,
@@ -13,7 +14,7 @@ pub struct ResetFrameCounter;
 pub(super) fn plugin(app: &mut App) {
     app.observe(reset_frame_counter);
     app.insert_resource(FrameCounter { count: 0 })
-        .add_systems(FixedUpdate, frame_count.in_set(AppSet::Update));
+        .add_systems(FixedUpdate, frame_count.in_set(AppSet::TickTimers));
 }
 
 fn reset_frame_counter(
@@ -23,7 +24,8 @@ fn reset_frame_counter(
     frame_counter.count = 0;
 }
 
-// The system to wait a few frames
+// The system to wait a few frames. This counts at the start of each tick, so
+// everything else in the tick sees the same count.
 fn frame_count(mut frame_counter: ResMut<FrameCounter>) {
     frame_counter.count += 1;
 }
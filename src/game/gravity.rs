@@ -0,0 +1,142 @@
+//! Which way is down.
+//!
+//! By default gravity pulls along world -Y while the level turns around the
+//! characters. A level can set its [`GravityMode`] so that gravity turns
+//! along with the level instead, or pulls each character towards whatever
+//! surface they last touched, which lets them walk up walls and along
+//! ceilings.
+//!
+//! Each character has a [`GravityFrame`] giving its current up direction.
+//! Its [`Velocity`](super::spawn::player::Velocity) is measured in that
+//! frame, so `y` is always up for the character and `x` is along the ground.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use serde::Deserialize;
+
+use super::{
+    spawn::{
+        level::{CurrentLevel, LevelMarker, Levels},
+        platform::Platform,
+    },
+    tiles::TileProperties,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<GravityFrame>();
+}
+
+/// Where gravity pulls characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect, Deserialize)]
+pub enum GravityMode {
+    /// Straight down the screen, whichever way the level is turned.
+    #[default]
+    World,
+    /// Towards the bottom of the level, turning along with it.
+    Level,
+    /// Towards the surface each character last touched.
+    Surface,
+}
+
+/// A character's local frame. Gravity pulls against `up`.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct GravityFrame {
+    /// Unit vector in world space.
+    pub up: Vec2,
+}
+
+impl Default for GravityFrame {
+    fn default() -> Self {
+        Self { up: Vec2::Y }
+    }
+}
+
+impl GravityFrame {
+    /// The direction along the ground to the character's right.
+    pub fn right(&self) -> Vec2 {
+        Vec2::new(self.up.y, -self.up.x)
+    }
+
+    /// Convert a vector in this frame into world space.
+    pub fn to_world(self, local: Vec2) -> Vec2 {
+        self.right() * local.x + self.up * local.y
+    }
+
+    /// Convert a vector in world space into this frame.
+    pub fn to_local(self, world: Vec2) -> Vec2 {
+        Vec2::new(world.dot(self.right()), world.dot(self.up))
+    }
+
+    /// The rotation that turns world up into this frame's up, for sprites.
+    pub fn rotation(&self) -> Quat {
+        Quat::from_rotation_z(Vec2::Y.angle_between(self.up))
+    }
+}
+
+/// Update each character's up direction for the level's [`GravityMode`].
+/// This has to happen before characters move.
+pub fn update_gravity_frames(
+    levels: Res<Levels>,
+    current_level: Res<CurrentLevel>,
+    level_query: Query<&Transform, With<LevelMarker>>,
+    mut character_query: Query<(
+        &GlobalTransform,
+        &mut GravityFrame,
+        Option<&KinematicCharacterControllerOutput>,
+    )>,
+    surface_query: Query<
+        (&GlobalTransform, Has<Platform>),
+        Or<(With<TileProperties>, With<Platform>)>,
+    >,
+) {
+    let mode = levels
+        .current(*current_level)
+        .map(|level| level.gravity_mode)
+        .unwrap_or_default();
+    let level_up = level_query.get_single().map_or(Vec2::Y, |transform| {
+        (transform.rotation * Vec3::Y).truncate()
+    });
+
+    for (global_transform, mut frame, output) in &mut character_query {
+        let up = match mode {
+            GravityMode::World => Vec2::Y,
+            GravityMode::Level => level_up,
+            GravityMode::Surface => {
+                // Keep the last surface's up while in the air.
+                let position = global_transform.translation().truncate();
+                output
+                    .into_iter()
+                    .flat_map(|output| &output.collisions)
+                    .filter_map(|collision| surface_query.get(collision.entity).ok())
+                    .map(|(surface_tf, is_platform)| {
+                        let delta = position - surface_tf.translation().truncate();
+                        (
+                            delta.length_squared(),
+                            surface_normal(surface_tf, delta, is_platform),
+                        )
+                    })
+                    .min_by(|(a, _), (b, _)| a.total_cmp(b))
+                    .map_or(frame.up, |(_, normal)| normal)
+            }
+        };
+        if frame.up != up {
+            frame.up = up;
+        }
+    }
+}
+
+/// The normal of the face of a surface facing `delta`, the offset from the
+/// surface's centre to the character. Tiles are square, so the face is
+/// whichever side the character is most off to. Platforms are wide and flat,
+/// so only their top and bottom count.
+fn surface_normal(surface_tf: &GlobalTransform, delta: Vec2, is_platform: bool) -> Vec2 {
+    let right = surface_tf.right().truncate();
+    let up = surface_tf.up().truncate();
+    let (along, across) = (delta.dot(right), delta.dot(up));
+    if !is_platform && along.abs() > across.abs() {
+        right * along.signum()
+    } else {
+        up * across.signum()
+    }
+}
@@ -0,0 +1,149 @@
+//! Hazards hurt the player when they touch them. Hazards are tiles with the
+//! `hazard` property (see [`super::tiles`]) and `Spikes` objects in the map.
+//!
+//! What getting hurt does depends on the level's [`HazardMode`]. Ducklings
+//! following the player that brush against a hazard are knocked loose too.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use serde::Deserialize;
+
+use super::{
+    audio::sfx::PlaySfx,
+    conga::{Following, KnockLoose},
+    movement::{PreviousPhysicalTranslation, VisualTranslation},
+    respawn::RespawnPlayer,
+    simulation::TICK_SECS,
+    spawn::{
+        duckling::DucklingHome,
+        level::{CurrentLevel, LevelMarker, Levels},
+        player::{Player, Velocity},
+    },
+};
+use crate::AppSet;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Hazard>();
+    app.observe(on_player_hurt);
+    app.add_systems(
+        FixedUpdate,
+        (
+            tick_invulnerable.in_set(AppSet::TickTimers),
+            knock_loose_on_hazard.in_set(AppSet::Update),
+        ),
+    );
+}
+
+/// How long the player can't be hurt again after touching a hazard.
+const INVULNERABLE_SECS: f32 = 1.5;
+/// Upwards speed the player is thrown back with when their ducklings scatter.
+const HURT_BOUNCE_SPEED: f32 = 300.0;
+
+/// What happens when the player touches a hazard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect, Deserialize)]
+pub enum HazardMode {
+    /// The player goes back to the start of the level.
+    #[default]
+    Respawn,
+    /// The ducklings following the player go back to where they started.
+    ScatterDucklings,
+}
+
+/// Marks a collider that hurts the player.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Hazard;
+
+/// The player touched a hazard.
+#[derive(Event, Debug)]
+pub struct PlayerHurt;
+
+/// A player who was hurt recently and can't be hurt again yet.
+#[derive(Component, Debug)]
+struct Invulnerable(Timer);
+
+fn on_player_hurt(
+    _trigger: Trigger<PlayerHurt>,
+    mut commands: Commands,
+    levels: Res<Levels>,
+    current_level: Res<CurrentLevel>,
+    level_query: Query<&Transform, (With<LevelMarker>, Without<Following>)>,
+    mut player_query: Query<(Entity, &mut Velocity), (With<Player>, Without<Invulnerable>)>,
+    mut duckling_query: Query<
+        (
+            &DucklingHome,
+            &mut Transform,
+            &mut PreviousPhysicalTranslation,
+            &mut VisualTranslation,
+        ),
+        With<Following>,
+    >,
+) {
+    let Ok((player, mut velocity)) = player_query.get_single_mut() else {
+        return;
+    };
+    let hazard_mode = levels
+        .current(*current_level)
+        .map(|level| level.hazard_mode)
+        .unwrap_or_default();
+
+    commands.trigger(PlaySfx::Hurt);
+    commands
+        .entity(player)
+        .insert(Invulnerable(Timer::from_seconds(
+            INVULNERABLE_SECS,
+            TimerMode::Once,
+        )));
+
+    match hazard_mode {
+        HazardMode::Respawn => commands.trigger(RespawnPlayer),
+        HazardMode::ScatterDucklings => {
+            // Homes are in level space, so they have turned with the level.
+            if let Ok(level_transform) = level_query.get_single() {
+                for (home, mut transform, mut previous, mut visual) in &mut duckling_query {
+                    let position =
+                        level_transform.transform_point(home.0.extend(transform.translation.z));
+                    transform.translation = position;
+                    previous.0 = position.truncate();
+                    visual.0 = position.truncate();
+                }
+            }
+            commands.trigger(KnockLoose(player));
+            velocity.y = HURT_BOUNCE_SPEED;
+        }
+    }
+}
+
+fn tick_invulnerable(mut commands: Commands, mut query: Query<(Entity, &mut Invulnerable)>) {
+    for (entity, mut invulnerable) in &mut query {
+        if invulnerable
+            .0
+            .tick(Duration::from_secs_f32(TICK_SECS))
+            .finished()
+        {
+            commands.entity(entity).remove::<Invulnerable>();
+        }
+    }
+}
+
+/// Following ducklings are sensors, so they only show up in collision events.
+fn knock_loose_on_hazard(
+    mut events: EventReader<CollisionEvent>,
+    mut commands: Commands,
+    duckling_query: Query<(), With<Following>>,
+    hazard_query: Query<(), With<Hazard>>,
+) {
+    for event in events.read() {
+        let CollisionEvent::Started(a, b, _) = *event else {
+            continue;
+        };
+        for (duckling, other) in [(a, b), (b, a)] {
+            if duckling_query.contains(duckling) && hazard_query.contains(other) {
+                commands.trigger(KnockLoose(duckling));
+                commands.trigger(PlaySfx::Hurt);
+            }
+        }
+    }
+}
@@ -0,0 +1,252 @@
+//! Maps abstract game actions to keyboard keys and gamepad buttons.
+//!
+//! Systems should read input through [`ActionInput`] rather than checking
+//! keys directly, so that the player can rebind controls from the controls
+//! screen. The [`InputMap`] is saved along with the settings.
+
+use std::collections::BTreeMap;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use serde::{Deserialize, Serialize};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<InputMap>();
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Reflect, Serialize, Deserialize,
+)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    Jump,
+    RotateCounterClockwise,
+    RotateClockwise,
+    Pause,
+    Restart,
+}
+
+impl Action {
+    pub const ALL: [Action; 7] = [
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Jump,
+        Action::RotateCounterClockwise,
+        Action::RotateClockwise,
+        Action::Pause,
+        Action::Restart,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::MoveLeft => "Move Left",
+            Action::MoveRight => "Move Right",
+            Action::Jump => "Jump",
+            Action::RotateCounterClockwise => "Rotate Left",
+            Action::RotateClockwise => "Rotate Right",
+            Action::Pause => "Pause",
+            Action::Restart => "Restart Level",
+        }
+    }
+}
+
+/// A single key or button that can trigger an [`Action`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    Gamepad(GamepadButtonType),
+}
+
+impl Binding {
+    /// A short name for the binding, for showing in the UI.
+    pub fn label(&self) -> String {
+        match self {
+            Binding::Key(key) => {
+                let name = format!("{key:?}");
+                let short = ["Key", "Digit", "Arrow"]
+                    .iter()
+                    .find_map(|prefix| name.strip_prefix(prefix));
+                match short {
+                    Some(short) => short.to_string(),
+                    None => name,
+                }
+            }
+            Binding::Gamepad(button) => format!("Pad {button:?}"),
+        }
+    }
+
+    pub fn is_gamepad(&self) -> bool {
+        matches!(self, Binding::Gamepad(_))
+    }
+}
+
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputMap {
+    bindings: BTreeMap<Action, Vec<Binding>>,
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        use Binding::{Gamepad, Key};
+
+        Self {
+            bindings: BTreeMap::from([
+                (
+                    Action::MoveLeft,
+                    vec![
+                        Key(KeyCode::KeyA),
+                        Key(KeyCode::ArrowLeft),
+                        Gamepad(GamepadButtonType::DPadLeft),
+                    ],
+                ),
+                (
+                    Action::MoveRight,
+                    vec![
+                        Key(KeyCode::KeyD),
+                        Key(KeyCode::ArrowRight),
+                        Gamepad(GamepadButtonType::DPadRight),
+                    ],
+                ),
+                (
+                    Action::Jump,
+                    vec![
+                        Key(KeyCode::KeyW),
+                        Key(KeyCode::ArrowUp),
+                        Gamepad(GamepadButtonType::South),
+                        Gamepad(GamepadButtonType::DPadUp),
+                    ],
+                ),
+                (
+                    Action::RotateCounterClockwise,
+                    vec![Key(KeyCode::KeyQ), Gamepad(GamepadButtonType::LeftTrigger)],
+                ),
+                (
+                    Action::RotateClockwise,
+                    vec![Key(KeyCode::KeyE), Gamepad(GamepadButtonType::RightTrigger)],
+                ),
+                (
+                    Action::Pause,
+                    vec![Key(KeyCode::Escape), Gamepad(GamepadButtonType::Start)],
+                ),
+                (
+                    Action::Restart,
+                    vec![Key(KeyCode::KeyR), Gamepad(GamepadButtonType::Select)],
+                ),
+            ]),
+        }
+    }
+}
+
+impl InputMap {
+    pub fn bindings(&self, action: Action) -> &[Binding] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Replace the bindings for `action` that use the same device as
+    /// `binding`, leaving bindings for the other device alone. A key or
+    /// button only triggers one action, so it is taken off any other action
+    /// that used it.
+    pub fn rebind(&mut self, action: Action, binding: Binding) {
+        for (other, bindings) in &mut self.bindings {
+            if *other != action && bindings.contains(&binding) {
+                log::warn!("{} was bound to {other:?}, unbinding it", binding.label());
+                bindings.retain(|b| *b != binding);
+            }
+        }
+
+        let bindings = self.bindings.entry(action).or_default();
+        bindings.retain(|b| b.is_gamepad() != binding.is_gamepad());
+        bindings.push(binding);
+    }
+}
+
+/// Reads the current state of each [`Action`] from all connected devices.
+#[derive(SystemParam)]
+pub struct ActionInput<'w> {
+    input_map: Res<'w, InputMap>,
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    gamepads: Res<'w, Gamepads>,
+    gamepad_buttons: Res<'w, ButtonInput<GamepadButton>>,
+}
+
+impl ActionInput<'_> {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.any_binding(
+            action,
+            |keys, key| keys.pressed(key),
+            |buttons, button| buttons.pressed(button),
+        )
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.any_binding(
+            action,
+            |keys, key| keys.just_pressed(key),
+            |buttons, button| buttons.just_pressed(button),
+        )
+    }
+
+    fn any_binding(
+        &self,
+        action: Action,
+        check_key: impl Fn(&ButtonInput<KeyCode>, KeyCode) -> bool,
+        check_button: impl Fn(&ButtonInput<GamepadButton>, GamepadButton) -> bool,
+    ) -> bool {
+        self.input_map
+            .bindings(action)
+            .iter()
+            .any(|binding| match *binding {
+                Binding::Key(key) => check_key(&self.keys, key),
+                Binding::Gamepad(button_type) => self.gamepads.iter().any(|gamepad| {
+                    check_button(
+                        &self.gamepad_buttons,
+                        GamepadButton::new(gamepad, button_type),
+                    )
+                }),
+            })
+    }
+}
+
+/// A run condition that is true on the frame `action` is pressed.
+pub fn action_just_pressed(action: Action) -> impl FnMut(ActionInput) -> bool + Clone {
+    move |input: ActionInput| input.just_pressed(action)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebinding_replaces_the_same_device() {
+        let mut input_map = InputMap::default();
+        input_map.rebind(Action::Jump, Binding::Key(KeyCode::Space));
+
+        assert_eq!(
+            input_map.bindings(Action::Jump),
+            [
+                Binding::Gamepad(GamepadButtonType::South),
+                Binding::Gamepad(GamepadButtonType::DPadUp),
+                Binding::Key(KeyCode::Space),
+            ]
+        );
+    }
+
+    #[test]
+    fn rebinding_takes_the_binding_off_other_actions() {
+        let mut input_map = InputMap::default();
+        input_map.rebind(Action::Restart, Binding::Key(KeyCode::Escape));
+
+        assert_eq!(
+            input_map.bindings(Action::Pause),
+            [Binding::Gamepad(GamepadButtonType::Start)]
+        );
+        assert_eq!(
+            input_map.bindings(Action::Restart),
+            [
+                Binding::Gamepad(GamepadButtonType::Select),
+                Binding::Key(KeyCode::Escape),
+            ]
+        );
+    }
+}
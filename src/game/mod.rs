@@ -2,25 +2,57 @@
 
 use bevy::prelude::*;
 
+mod ai;
 mod animation;
 pub mod assets;
 pub mod audio;
 pub mod camera;
+pub mod campaign;
+pub mod conga;
+pub mod fox;
 pub mod frames;
+pub mod gravity;
+pub mod hazard;
+pub mod input;
 mod movement;
+pub mod progress;
+pub mod puzzle;
+pub mod replay;
+mod respawn;
+pub mod rotation;
+mod save;
 pub mod score;
 pub mod settings;
+pub mod simulation;
 pub mod spawn;
+pub mod tiles;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
         animation::plugin,
         audio::plugin,
         assets::plugin,
-        movement::plugin,
+        campaign::plugin,
+        (
+            movement::plugin,
+            gravity::plugin,
+            rotation::plugin,
+            respawn::plugin,
+            conga::plugin,
+            ai::plugin,
+            fox::plugin,
+            tiles::plugin,
+            hazard::plugin,
+            puzzle::plugin,
+        ),
         spawn::plugin,
         camera::plugin,
         frames::plugin,
+        input::plugin,
         score::plugin,
+        progress::plugin,
+        save::plugin,
+        simulation::plugin,
+        replay::plugin,
     ));
 }
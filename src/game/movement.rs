@@ -1,28 +1,49 @@
 //! Handle player input and translate it into movement.
-//! Note that the approach used here is simple for demonstration purposes.
-//! If you want to move the player in a smoother way,
-//! consider using a [fixed timestep](https://github.com/bevyengine/bevy/blob/latest/examples/movement/physics_in_fixed_timestep.rs).
+//! Input is read and movement applied once per simulation tick in
+//! `FixedUpdate` (see [`super::simulation`]), and sprites are drawn
+//! in between ticks in `Update`.
 
 use bevy::prelude::*;
 use bevy_rapier2d::{
-    control::{KinematicCharacterController, KinematicCharacterControllerOutput},
+    control::{
+        CharacterCollision, KinematicCharacterController, KinematicCharacterControllerOutput,
+    },
     geometry::Collider,
 };
 
 pub const GRAVITY: f32 = -9.81 * 32.0 * 4.0;
 pub const TERMINAL_VELOCITY: f32 = -420.0;
+/// How quickly horizontal speed changes on a tile with a friction of 1, in
+/// pixels per second squared. Only slippery tiles use this, everywhere else
+/// the speed changes straight away.
+const GROUND_ACCELERATION: f32 = 3000.0;
+/// Landing slower than this on a bouncy tile doesn't bounce, so that
+/// characters can come to rest on it.
+const MIN_BOUNCE_SPEED: f32 = 60.0;
+/// How quickly a [`Knockback`] wears off, in pixels per second squared.
+const KNOCKBACK_DECAY: f32 = 1200.0;
+/// How sideways a surface has to face to count as a wall, as the sideways
+/// part of its normal in the player's frame.
+const WALL_NORMAL_MIN: f32 = 0.7;
 
 use crate::AppSet;
 
 use super::{
     audio::sfx::PlaySfx,
     frames::FrameCounter,
-    score::DucklingCollected,
+    gravity::{update_gravity_frames, GravityFrame},
+    hazard::{Hazard, PlayerHurt},
+    input::{Action, ActionInput},
+    rotation::{LevelClock, PlayerRotation, RotationController},
+    score::{DucklingCollected, Score},
+    simulation::TICK_SECS,
     spawn::{
         duckling::Duckling,
-        level::{EndLevel, LevelFinishPoint, LevelMarker},
+        level::{CurrentLevel, EndLevel, LevelCompleted, LevelFinishPoint, LevelMarker, Levels},
+        platform::{move_platforms, GroundPlatform, Platform},
         player::{IsOnGround, Player, SpriteMarker, Velocity},
     },
+    tiles::{GroundTile, TileProperties},
 };
 
 pub(super) fn plugin(app: &mut App) {
@@ -30,17 +51,20 @@ pub(super) fn plugin(app: &mut App) {
     app.register_type::<MovementController>();
     app.add_systems(
         Update,
-        (
-            update_rendered_transform,
-            update_sprite_transform,
-            record_movement_controller,
-        )
+        (update_rendered_transform, update_sprite_transform)
             .chain()
             .in_set(AppSet::RecordInput),
     );
+    app.add_systems(
+        FixedUpdate,
+        record_movement_controller.in_set(AppSet::RecordInput),
+    );
 
     // Apply movement based on controls.
     app.register_type::<Movement>();
+    app.register_type::<Knockback>();
+    app.register_type::<JumpState>();
+    app.register_type::<WallContact>();
     app.add_systems(
         Update,
         (apply_sprite_direction,).chain().in_set(AppSet::Update),
@@ -49,6 +73,8 @@ pub(super) fn plugin(app: &mut App) {
         FixedUpdate,
         (
             save_prev_translation,
+            move_platforms,
+            update_gravity_frames,
             apply_movement,
             detect_ground,
             rotate_world,
@@ -64,28 +90,39 @@ pub(super) fn plugin(app: &mut App) {
 #[reflect(Component)]
 pub struct MovementController(pub Vec2);
 
-fn record_movement_controller(
-    input: Res<ButtonInput<KeyCode>>,
-    mut controller_query: Query<&mut MovementController>,
+/// Stick input smaller than this is ignored.
+const GAMEPAD_DEADZONE: f32 = 0.2;
+
+pub fn record_movement_controller(
+    input: ActionInput,
+    gamepads: Res<Gamepads>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    mut controller_query: Query<&mut MovementController, With<Player>>,
 ) {
-    // Collect directional input.
+    // Collect directional input from whatever is bound to each action.
     let mut intent = Vec2::ZERO;
-    if input.pressed(KeyCode::KeyW) || input.pressed(KeyCode::ArrowUp) {
+    if input.pressed(Action::Jump) {
         intent.y += 1.0;
     }
-    if input.pressed(KeyCode::KeyS) || input.pressed(KeyCode::ArrowDown) {
-        intent.y -= 1.0;
-    }
-    if input.pressed(KeyCode::KeyA) || input.pressed(KeyCode::ArrowLeft) {
+    if input.pressed(Action::MoveLeft) {
         intent.x -= 1.0;
     }
-    if input.pressed(KeyCode::KeyD) || input.pressed(KeyCode::ArrowRight) {
+    if input.pressed(Action::MoveRight) {
         intent.x += 1.0;
     }
 
-    // Normalize so that diagonal movement has the same speed as
+    // Merge in the gamepad's left stick. Only the horizontal axis is used,
+    // so that pushing it slightly upwards doesn't jump.
+    for gamepad in gamepads.iter() {
+        let stick_x = gamepad_axes
+            .get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX))
+            .unwrap_or_default();
+        intent.x += apply_deadzone(stick_x);
+    }
+
+    // Limit the length so that diagonal movement has the same speed as
     // horizontal and vertical movement.
-    let intent = intent.normalize_or_zero();
+    let intent = intent.clamp_length_max(1.0);
 
     // Apply movement intent to controllers.
     for mut controller in &mut controller_query {
@@ -93,6 +130,16 @@ fn record_movement_controller(
     }
 }
 
+/// Ignore small stick movements and rescale the rest so that input still
+/// starts from zero just outside the deadzone.
+fn apply_deadzone(value: f32) -> f32 {
+    if value.abs() < GAMEPAD_DEADZONE {
+        return 0.0;
+    }
+    let scaled = ((value.abs() - GAMEPAD_DEADZONE) / (1.0 - GAMEPAD_DEADZONE)).min(1.0);
+    scaled * value.signum()
+}
+
 #[derive(Component, Reflect)]
 #[reflect(Component)]
 pub struct Movement {
@@ -102,45 +149,302 @@ pub struct Movement {
     /// Note that physics engines may use different unit/pixel ratios.
     pub speed: f32,
     pub jump_speed: f32,
+    /// How long after walking off a ledge a jump still works, in seconds.
+    pub coyote_time: f32,
+    /// How long before landing a jump press is remembered, in seconds.
+    pub jump_buffer: f32,
+    /// Upwards speed is multiplied by this when jump is let go of early.
+    pub jump_cut: f32,
+    /// Gravity is multiplied by this near the top of a jump.
+    pub apex_gravity: f32,
+    /// Vertical speed below which a jump counts as being at its top, in
+    /// pixels per second.
+    pub apex_speed: f32,
+    /// Fastest fall while sliding down a wall, in pixels per second.
+    pub wall_slide_speed: f32,
+    /// Speed away from the wall and upwards given by a wall jump.
+    pub wall_jump_speed: Vec2,
+}
+
+impl Default for Movement {
+    fn default() -> Self {
+        Self {
+            speed: 200.0,
+            jump_speed: 400.0,
+            coyote_time: 0.1,
+            jump_buffer: 0.12,
+            jump_cut: 0.5,
+            apex_gravity: 0.5,
+            apex_speed: 40.0,
+            wall_slide_speed: 80.0,
+            wall_jump_speed: Vec2::new(260.0, 380.0),
+        }
+    }
+}
+
+/// Keeps track of jump input and ground contact over time, for coyote time,
+/// jump buffering and cutting jumps short. See [`Movement`] for the settings.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct JumpState {
+    /// Seconds since the character was last on the ground.
+    since_grounded: f32,
+    /// Seconds since jump was last pressed.
+    since_pressed: f32,
+    /// Whether jump was held on the previous step.
+    was_held: bool,
+    /// Whether the character is still rising from a jump that can be cut short.
+    rising: bool,
+}
+
+impl Default for JumpState {
+    fn default() -> Self {
+        Self {
+            since_grounded: f32::INFINITY,
+            since_pressed: f32::INFINITY,
+            was_held: false,
+            rising: false,
+        }
+    }
+}
+
+impl JumpState {
+    /// Record this step's ground contact and jump input, and return whether
+    /// to jump. Holding jump keeps jumping on every landing.
+    fn update(
+        &mut self,
+        movement: &Movement,
+        on_ground: bool,
+        held: bool,
+        delta_seconds: f32,
+    ) -> bool {
+        if on_ground && !self.rising {
+            self.since_grounded = 0.0;
+        } else {
+            self.since_grounded += delta_seconds;
+        }
+        if held && !self.was_held {
+            self.since_pressed = 0.0;
+        } else {
+            self.since_pressed += delta_seconds;
+        }
+        self.was_held = held;
+
+        let wants_jump = held || self.since_pressed <= movement.jump_buffer;
+        if !wants_jump || self.since_grounded > movement.coyote_time {
+            return false;
+        }
+
+        // Use up both windows, so one press only jumps once.
+        self.since_grounded = f32::INFINITY;
+        self.since_pressed = f32::INFINITY;
+        self.rising = true;
+        true
+    }
+
+    /// Use up a recent jump press, for jumps off something other than the
+    /// ground.
+    fn take_press(&mut self, movement: &Movement) -> bool {
+        if self.since_pressed > movement.jump_buffer {
+            return false;
+        }
+        self.since_pressed = f32::INFINITY;
+        self.rising = true;
+        true
+    }
+}
+
+/// A wall next to the player, for wall slides and wall jumps. This is only
+/// filled in for levels that allow wall jumps.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct WallContact {
+    /// -1 for a wall on the left, 1 for a wall on the right and 0 for no
+    /// wall, in the player's frame.
+    pub side: f32,
+    /// Whether the player is sliding down the wall.
+    pub sliding: bool,
+    /// Seconds since the last wall jump.
+    pub since_wall_jump: f32,
+}
+
+impl Default for WallContact {
+    fn default() -> Self {
+        Self {
+            side: 0.0,
+            sliding: false,
+            since_wall_jump: f32::INFINITY,
+        }
+    }
 }
 
 fn apply_movement(
-    time: Res<Time>,
     mut commands: Commands,
     mut movement_query: Query<(
         &MovementController,
         &Movement,
         &mut Velocity,
+        &mut JumpState,
+        &GravityFrame,
         &mut KinematicCharacterController,
         &IsOnGround,
+        &Transform,
+        Option<&GroundTile>,
+        Option<&GroundPlatform>,
+        Option<&mut Knockback>,
+        Option<&mut WallContact>,
+        Has<Player>,
+        Entity,
     )>,
+    platform_query: Query<(&Platform, &GlobalTransform)>,
 ) {
-    for (controller, movement, mut velocity, mut char_controller, is_on_ground) in
-        movement_query.iter_mut()
+    for (
+        controller,
+        movement,
+        mut velocity,
+        mut jump,
+        frame,
+        mut char_controller,
+        is_on_ground,
+        transform,
+        ground_tile,
+        ground_platform,
+        knockback,
+        mut wall,
+        is_player,
+        entity,
+    ) in movement_query.iter_mut()
     {
-        // X velocity doesn't accumulate.
-        velocity.x = movement.speed * controller.0.x;
+        let delta_seconds = TICK_SECS;
+        // Special tiles only have an effect while standing on them.
+        let ground = ground_tile
+            .filter(|_| is_on_ground.is_on_ground)
+            .copied()
+            .unwrap_or_default();
+        let tile = ground.properties;
+
+        // X velocity doesn't accumulate, except on slippery ground.
+        let target_x = movement.speed * controller.0.x * tile.speed_factor();
+        if tile.is_slippery() {
+            let max_change = GROUND_ACCELERATION * tile.friction * delta_seconds;
+            velocity.x += (target_x - velocity.x).clamp(-max_change, max_change);
+        } else {
+            velocity.x = target_x;
+        }
 
-        if controller.0.y > 0.0 {
-            // Jumping.
-            if is_on_ground.is_on_ground {
-                velocity.y = movement.jump_speed;
+        // Jumping. Jumps still work a moment after leaving the ground, and
+        // a press just before landing jumps as soon as the character lands.
+        let jump_held = controller.0.y > 0.0;
+        let wall_side = wall
+            .as_ref()
+            .filter(|_| !is_on_ground.is_on_ground)
+            .map_or(0.0, |wall| wall.side);
+        if jump.update(
+            movement,
+            is_on_ground.is_on_ground,
+            jump_held,
+            delta_seconds,
+        ) {
+            velocity.y = movement.jump_speed * tile.speed_factor();
+            if is_player {
+                commands.trigger(PlaySfx::Jump);
+            }
+        } else if wall_side != 0.0 && jump.take_press(movement) {
+            // Kick off away from the wall. Sideways speed doesn't last from
+            // one step to the next, so the kick is a knockback.
+            velocity.y = movement.wall_jump_speed.y;
+            let away = -wall_side * frame.right() * movement.wall_jump_speed.x;
+            commands.entity(entity).insert(Knockback(away));
+            if let Some(wall) = wall.as_mut() {
+                wall.since_wall_jump = 0.0;
+            }
+            if is_player {
                 commands.trigger(PlaySfx::Jump);
             }
+        } else if jump.rising && (velocity.y <= 0.0 || !jump_held) {
+            // Letting go of jump early cuts the jump short.
+            if velocity.y > 0.0 {
+                velocity.y *= movement.jump_cut;
+            }
+            jump.rising = false;
         }
 
-        // Y velocity does, but only up to terminal velocity.
+        // Y velocity does, but only up to terminal velocity. Bouncy ground
+        // sends some of it back up again.
         if is_on_ground.is_on_ground && velocity.y < 0.0 {
-            velocity.y = 0.0;
+            velocity.y = bounce_speed(&tile, velocity.y);
         }
 
-        velocity.y += GRAVITY * time.delta_seconds();
+        // Gravity eases off at the top of a jump, which gives a bit more
+        // time to line up the landing.
+        let near_apex = !is_on_ground.is_on_ground && velocity.y.abs() < movement.apex_speed;
+        let gravity = if near_apex {
+            GRAVITY * movement.apex_gravity
+        } else {
+            GRAVITY
+        };
+        velocity.y += gravity * delta_seconds;
         if velocity.y < TERMINAL_VELOCITY {
             velocity.y = TERMINAL_VELOCITY;
         }
 
+        // Pushing into a wall while falling slides down it slowly.
+        if let Some(wall) = wall.as_mut() {
+            wall.since_wall_jump += delta_seconds;
+            wall.sliding = wall_side != 0.0 && velocity.y < 0.0 && controller.0.x * wall_side > 0.0;
+            if wall.sliding {
+                velocity.y = velocity.y.max(-movement.wall_slide_speed);
+            }
+        }
+
+        // Conveyors carry whatever stands on them along, and so do platforms.
+        let carried = ground.along * tile.conveyor_speed;
+        let platform_carry = ground_platform
+            .and_then(|platform| platform.0)
+            .filter(|_| is_on_ground.is_on_ground)
+            .and_then(|entity| platform_query.get(entity).ok())
+            .map_or(Vec2::ZERO, |(platform, platform_transform)| {
+                platform.carry(
+                    platform_transform.translation().truncate(),
+                    transform.translation.truncate(),
+                )
+            });
+
+        // Knockback pushes on top of everything else until it wears off.
+        let knocked = match knockback {
+            Some(mut knockback) => {
+                let pushed = knockback.0;
+                let speed = (pushed.length() - KNOCKBACK_DECAY * delta_seconds).max(0.0);
+                knockback.0 = pushed.clamp_length_max(speed);
+                if speed == 0.0 {
+                    commands.entity(entity).remove::<Knockback>();
+                }
+                pushed
+            }
+            None => Vec2::ZERO,
+        };
+
+        // Velocity is in the character's own frame, everything else is
+        // already in world space.
+        let moved = frame.to_world(Vec2::new(velocity.x, velocity.y));
+        char_controller.up = frame.up;
         char_controller.translation =
-            Some(Vec2::new(velocity.x, velocity.y) * time.delta_seconds());
+            Some((moved + carried + knocked) * delta_seconds + platform_carry);
+    }
+}
+
+/// A push in world space, in pixels per second, that wears off over time.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Knockback(pub Vec2);
+
+/// The upwards speed after landing on `tile` while falling at `falling_speed`.
+fn bounce_speed(tile: &TileProperties, falling_speed: f32) -> f32 {
+    let speed = -falling_speed * tile.restitution;
+    if speed < MIN_BOUNCE_SPEED {
+        0.0
+    } else {
+        speed
     }
 }
 
@@ -154,23 +458,24 @@ fn save_prev_translation(
 
 fn detect_ground(
     mut controllers: Query<(
-        Entity,
         &mut IsOnGround,
         &mut Velocity,
+        &GravityFrame,
         &KinematicCharacterControllerOutput,
     )>,
 ) {
-    for (_, mut is_on_ground, mut velocity, output) in controllers.iter_mut() {
+    for (mut is_on_ground, mut velocity, frame, output) in controllers.iter_mut() {
+        // Up and down are relative to the character's own frame.
+        let desired = frame.to_local(output.desired_translation);
+        let effective = frame.to_local(output.effective_translation);
         if !is_on_ground.is_on_ground {
-            is_on_ground.is_on_ground = output.grounded
-                && output.desired_translation.y < 0.0
-                && output.effective_translation.y >= -1.0;
+            is_on_ground.is_on_ground = output.grounded && desired.y < 0.0 && effective.y >= -1.0;
         } else if !output.grounded {
             is_on_ground.is_on_ground = false;
         }
 
         // Did we hit our head?
-        if output.desired_translation.y > 0.0 && output.effective_translation.y <= 0.5 {
+        if desired.y > 0.0 && effective.y <= 0.5 {
             velocity.y = 0.0;
         }
     }
@@ -191,45 +496,147 @@ fn apply_sprite_direction(
     }
 }
 
-fn rotate_world(time: Res<Time>, mut query: Query<&mut Transform, With<LevelMarker>>) {
-    for mut transform in query.iter_mut() {
-        transform.rotate(Quat::from_rotation_z(f32::to_radians(
-            5. * time.delta_seconds(),
-        )));
+fn rotate_world(
+    levels: Res<Levels>,
+    current_level: Res<CurrentLevel>,
+    mut clock: ResMut<LevelClock>,
+    mut query: Query<(&mut Transform, &RotationController, &mut PlayerRotation), With<LevelMarker>>,
+) {
+    let Some(level) = levels.current(*current_level) else {
+        return;
+    };
+
+    clock.tick();
+    let mut angle = 0.0;
+    if level.rotation_mode.is_passive() {
+        angle += level.rotation.angle(clock.elapsed_secs());
+    }
+
+    for (mut transform, controller, mut player_rotation) in query.iter_mut() {
+        let mut angle = angle;
+        if level.rotation_mode.is_player_driven() {
+            player_rotation.update(controller.0, TICK_SECS);
+            angle += player_rotation.angle;
+        }
+        transform.rotation = Quat::from_rotation_z(angle.to_radians());
     }
 }
 
 fn read_character_controller_collisions(
-    time: Res<Time>,
     mut commands: Commands,
-    mut character_controller_outputs: Query<(
+    levels: Res<Levels>,
+    current_level: Res<CurrentLevel>,
+    mut character_controller_outputs: Query<
+        (
+            &GlobalTransform,
+            &mut Transform,
+            &KinematicCharacterControllerOutput,
+            &mut GroundTile,
+            &mut GroundPlatform,
+            &mut WallContact,
+            &GravityFrame,
+        ),
+        With<Player>,
+    >,
+    colliders: Query<(
         &GlobalTransform,
-        &mut Transform,
-        &KinematicCharacterControllerOutput,
+        &Collider,
+        Option<&Duckling>,
+        Option<&TileProperties>,
+        Has<Hazard>,
+        Has<Platform>,
     )>,
-    colliders: Query<(&GlobalTransform, &Collider, Option<&Duckling>)>,
 ) {
-    for (global_transform, mut transform, output) in character_controller_outputs.iter_mut() {
+    let wall_jumps = levels
+        .current(*current_level)
+        .is_some_and(|level| level.wall_jumps);
+
+    for (
+        global_transform,
+        mut transform,
+        output,
+        mut ground_tile,
+        mut ground_platform,
+        mut wall_contact,
+        frame,
+    ) in character_controller_outputs.iter_mut()
+    {
+        let mut ground = GroundTile::default();
+        let mut platform = None;
+        let mut wall_side = 0.0;
         for collision in &output.collisions {
             // move the ball away from the collision.
-            if let Ok((collider_tf, _collider, is_duckling)) = colliders.get(collision.entity) {
+            if let Ok((collider_tf, _collider, is_duckling, tile, is_hazard, is_platform)) =
+                colliders.get(collision.entity)
+            {
                 if is_duckling.is_some() {
                     // Collect Duckling.
                     commands.trigger(DucklingCollected(collision.entity));
                 } else {
+                    if is_hazard {
+                        commands.trigger(PlayerHurt);
+                    }
+
                     let delta = global_transform.translation() - collider_tf.translation();
+                    // Above and below are in the player's own frame.
+                    let local = frame.to_local(delta.truncate());
+                    if is_platform {
+                        // Platforms are wide, so anything above the middle counts
+                        // as standing on it. They carry the player instead of
+                        // pushing them away.
+                        if local.y > 0.0 {
+                            platform = Some(collision.entity);
+                        }
+                        continue;
+                    }
+
+                    if let Some(tile) = tile {
+                        // A tile more below the player than to the side is
+                        // the one they are standing on.
+                        if local.y > local.x.abs() {
+                            ground = GroundTile {
+                                properties: *tile,
+                                along: collider_tf.right().truncate(),
+                            };
+                        }
+
+                        // In levels with wall jumps, walls hold on to the
+                        // player rather than pushing them away.
+                        let normal = frame.to_local(collision_normal(collision, delta.truncate()));
+                        if wall_jumps && normal.x.abs() > WALL_NORMAL_MIN {
+                            wall_side = -normal.x.signum();
+                            continue;
+                        }
+                    }
+
                     let distance = delta.length();
                     if distance < 64.0 {
                         let direction = delta.normalize();
                         let movement = direction * (64.0 - distance);
-                        transform.translation += movement * time.delta_seconds();
+                        transform.translation += movement * TICK_SECS;
                     }
                 }
             }
         }
+        *ground_tile = ground;
+        ground_platform.0 = platform;
+        if wall_contact.side != wall_side {
+            wall_contact.side = wall_side;
+        }
     }
 }
 
+/// The normal of the surface the character ran into, pointing away from it.
+/// Falls back to the direction from the collider's centre if the collision
+/// has no contact details.
+fn collision_normal(collision: &CharacterCollision, delta: Vec2) -> Vec2 {
+    collision
+        .hit
+        .details
+        .as_ref()
+        .map_or_else(|| delta.normalize_or_zero(), |details| -details.normal1)
+}
+
 #[derive(Debug, Component, Clone, Copy, PartialEq, Default, Deref, DerefMut)]
 pub struct PreviousPhysicalTranslation(pub Vec2);
 
@@ -273,17 +680,23 @@ pub struct SpriteOffset(pub Vec2);
 /// Then this system will update the sprite's transform to match the visual transform.
 fn update_sprite_transform(
     mut sprite_query: Query<(&Parent, &mut Transform, Option<&SpriteOffset>), With<SpriteMarker>>,
-    parent_query: Query<(&Transform, &VisualTranslation), Without<SpriteMarker>>,
+    parent_query: Query<
+        (&Transform, &VisualTranslation, Option<&GravityFrame>),
+        Without<SpriteMarker>,
+    >,
 ) {
     for (parent, mut transform, sprite_offset) in sprite_query.iter_mut() {
-        if let Ok((parent_transform, visual)) = parent_query.get(parent.get()) {
+        if let Ok((parent_transform, visual, frame)) = parent_query.get(parent.get()) {
+            let frame = frame.copied().unwrap_or_default();
             let mut new_offset = visual.0 - parent_transform.translation.truncate();
             if let Some(offset) = sprite_offset {
-                new_offset += offset.0;
+                new_offset += frame.to_world(offset.0);
             }
 
             // Set the new sprite transform to match the parent's visual transform relative to the parent transform.
             transform.translation = new_offset.extend(0.);
+            // Stand the sprite up in the parent's frame.
+            transform.rotation = frame.rotation();
         }
     }
 }
@@ -291,19 +704,28 @@ fn update_sprite_transform(
 fn check_level_end(
     mut commands: Commands,
     frame_counter: Res<FrameCounter>,
+    score: Res<Score>,
     player_query: Query<&GlobalTransform, (With<Player>, Without<LevelFinishPoint>)>,
-    end_level_pos: Query<&GlobalTransform, (With<LevelFinishPoint>, Without<Player>)>,
+    end_level_pos: Query<(Entity, &GlobalTransform, &LevelFinishPoint), Without<Player>>,
 ) {
     if frame_counter.count < 10 {
         return;
     }
 
     for player_tf in player_query.iter() {
-        for end_tf in end_level_pos.iter() {
+        for (entity, end_tf, finish_point) in end_level_pos.iter() {
+            if !finish_point.is_active(score.ducklings_collected) {
+                continue;
+            }
+
             let distance = player_tf.translation().distance(end_tf.translation());
             if distance < 16.0 {
                 log::info!("Level end reached!");
+                // Only finish once, even if the player stays on the exit.
+                commands.entity(entity).remove::<LevelFinishPoint>();
+                commands.trigger(LevelCompleted);
                 commands.trigger(EndLevel);
+                return;
             }
         }
     }
@@ -0,0 +1,97 @@
+//! Tracks which levels the player has completed and their best results.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    frames::FrameCounter,
+    replay::ReplayPlayback,
+    score::{OverallScore, Score},
+    spawn::level::{CurrentLevel, GameCompleted, LevelCompleted, LevelLayout},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Progress>();
+    app.observe(record_level_completed);
+    app.observe(record_game_completed);
+}
+
+/// The player's results for a single level.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LevelRecord {
+    pub completed: bool,
+    /// Fastest completion time in simulation ticks, counted from when the
+    /// level finished loading like replays are (see [`super::replay`]).
+    pub best_ticks: Option<u32>,
+    pub ducklings_collected: u32,
+    pub ducklings_total: u32,
+    pub best_score: u32,
+}
+
+#[derive(Resource, Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Progress {
+    /// Results for each level, indexed by position in the campaign.
+    levels: Vec<LevelRecord>,
+    /// Fastest time in seconds for a run through the whole campaign.
+    pub best_run: Option<f32>,
+}
+
+impl Progress {
+    pub fn record(&self, index: usize) -> LevelRecord {
+        self.levels.get(index).copied().unwrap_or_default()
+    }
+
+    /// The first level is always unlocked. Every other level is unlocked
+    /// by completing the one before it.
+    pub fn is_unlocked(&self, index: usize) -> bool {
+        index == 0 || self.record(index - 1).completed
+    }
+
+    pub(super) fn record_mut(&mut self, index: usize) -> &mut LevelRecord {
+        if self.levels.len() <= index {
+            self.levels.resize(index + 1, LevelRecord::default());
+        }
+        &mut self.levels[index]
+    }
+}
+
+fn record_level_completed(
+    _trigger: Trigger<LevelCompleted>,
+    current_level: Res<CurrentLevel>,
+    frame_counter: Res<FrameCounter>,
+    score: Res<Score>,
+    layout: Res<LevelLayout>,
+    playback: Option<Res<ReplayPlayback>>,
+    mut progress: ResMut<Progress>,
+) {
+    // Watching a replay doesn't count as completing the level again.
+    if playback.is_some() {
+        return;
+    }
+
+    let ticks = frame_counter.count;
+    let record = progress.record_mut(current_level.index());
+    record.completed = true;
+    record.best_ticks = Some(record.best_ticks.map_or(ticks, |t| t.min(ticks)));
+    record.ducklings_collected = record.ducklings_collected.max(score.ducklings_collected);
+    // Foxes lower the score's total as they eat ducklings, so count them
+    // from the map instead.
+    record.ducklings_total = layout.ducklings.len() as u32;
+    record.best_score = record.best_score.max(score.score);
+}
+
+fn record_game_completed(
+    _trigger: Trigger<GameCompleted>,
+    overall_score: Res<OverallScore>,
+    mut progress: ResMut<Progress>,
+) {
+    // Runs started from the level select screen skip levels, so they don't count.
+    if !overall_score.full_run {
+        return;
+    }
+
+    let run_time = overall_score.total_seconds;
+    progress.best_run = Some(progress.best_run.map_or(run_time, |t| t.min(run_time)));
+}
@@ -0,0 +1,296 @@
+//! Keys, locked doors and switches.
+//!
+//! Keys are `Key` objects in the map with a `color` property. Doors are tiles
+//! with a `door` property naming the key that opens them (see
+//! [`super::tiles`]), and they open for good once the player holds that key.
+//!
+//! Switches are `Switch` objects, pressed by walking into them. They either
+//! turn a tile layer on and off, or reverse the world rotation. A tile layer
+//! with the `start_hidden` property starts off turned off.
+
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use bevy_ecs_tiled::prelude::TiledMapTileLayer;
+use bevy_ecs_tilemap::prelude::{TilePos, TileStorage, TileVisible};
+use bevy_rapier2d::prelude::*;
+
+use super::{
+    assets::SfxKey,
+    audio::sfx::PlaySfx,
+    rotation::LevelClock,
+    spawn::{
+        key::Key,
+        level::{string_property, LevelLayout, SpawnLevel},
+        player::Player,
+        switch::Switch,
+    },
+    tiles::TileProperties,
+};
+use crate::AppSet;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<KeyRing>();
+    app.init_resource::<ToggledLayers>();
+    app.register_type::<Door>();
+    app.observe(reset_puzzles);
+    app.observe(on_key_collected);
+    app.observe(on_switch_pressed);
+    app.add_systems(
+        FixedUpdate,
+        (
+            collect_keys,
+            press_switches,
+            open_doors,
+            update_switched_layers,
+        )
+            .chain()
+            .in_set(AppSet::Update),
+    );
+}
+
+/// How close the player has to get to a key to pick it up.
+const KEY_PICKUP_DISTANCE: f32 = 20.0;
+/// How close the player has to get to a switch to press it.
+const SWITCH_PRESS_DISTANCE: f32 = 20.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Reflect)]
+pub enum KeyColor {
+    #[default]
+    Red,
+    Green,
+    Blue,
+}
+
+impl KeyColor {
+    /// Parse the name used for the `color` property in Tiled.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "red" => Some(KeyColor::Red),
+            "green" => Some(KeyColor::Green),
+            "blue" => Some(KeyColor::Blue),
+            _ => None,
+        }
+    }
+
+    /// Tint for the key sprite.
+    pub fn color(self) -> Color {
+        match self {
+            KeyColor::Red => Color::srgb(0.9, 0.2, 0.2),
+            KeyColor::Green => Color::srgb(0.2, 0.8, 0.3),
+            KeyColor::Blue => Color::srgb(0.25, 0.45, 0.95),
+        }
+    }
+}
+
+/// What a switch does when it is pressed.
+#[derive(Debug, Clone, PartialEq, Eq, Reflect)]
+pub enum SwitchAction {
+    /// Turn the tile layer with this name on or off.
+    ToggleLayer(String),
+    /// Play the level's rotation backwards, or forwards again.
+    ReverseRotation,
+}
+
+impl SwitchAction {
+    /// Read the action from a `Switch` object's properties.
+    pub fn from_properties(properties: &tiled::Properties) -> Option<Self> {
+        match string_property(properties, "action")? {
+            "toggle_layer" => {
+                string_property(properties, "layer").map(|layer| Self::ToggleLayer(layer.into()))
+            }
+            "reverse_rotation" => Some(Self::ReverseRotation),
+            action => {
+                log::warn!("Unknown switch action: {action}");
+                None
+            }
+        }
+    }
+}
+
+/// The keys the player has picked up in the current level.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct KeyRing(HashSet<KeyColor>);
+
+impl KeyRing {
+    pub fn has(&self, color: KeyColor) -> bool {
+        self.0.contains(&color)
+    }
+}
+
+/// Tile layers that have been switched from how they started.
+#[derive(Resource, Debug, Clone, Default)]
+struct ToggledLayers(HashSet<String>);
+
+/// Marks the collider of a door tile, which opens with the key of this color.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Door(pub KeyColor);
+
+#[derive(Event, Debug)]
+pub struct KeyCollected(pub Entity);
+
+#[derive(Event, Debug)]
+pub struct SwitchPressed(pub Entity);
+
+fn reset_puzzles(
+    _trigger: Trigger<SpawnLevel>,
+    mut key_ring: ResMut<KeyRing>,
+    mut toggled: ResMut<ToggledLayers>,
+) {
+    key_ring.0.clear();
+    toggled.0.clear();
+}
+
+fn collect_keys(
+    mut commands: Commands,
+    player_query: Query<&GlobalTransform, With<Player>>,
+    key_query: Query<(Entity, &GlobalTransform), With<Key>>,
+) {
+    for player_tf in &player_query {
+        for (entity, key_tf) in &key_query {
+            if player_tf.translation().distance(key_tf.translation()) < KEY_PICKUP_DISTANCE {
+                commands.trigger(KeyCollected(entity));
+            }
+        }
+    }
+}
+
+fn on_key_collected(
+    trigger: Trigger<KeyCollected>,
+    mut commands: Commands,
+    mut key_ring: ResMut<KeyRing>,
+    key_query: Query<&Key>,
+) {
+    let entity = trigger.event().0;
+    let Ok(key) = key_query.get(entity) else {
+        return;
+    };
+
+    log::info!("Picked up {:?} key", key.color);
+    key_ring.0.insert(key.color);
+    commands.entity(entity).despawn_recursive();
+    commands.trigger(PlaySfx::CollectKey);
+}
+
+/// Open every door the player has the key for. Open doors lose their
+/// collider and their tile is hidden.
+fn open_doors(
+    mut commands: Commands,
+    key_ring: Res<KeyRing>,
+    door_query: Query<(Entity, &Door), Without<ColliderDisabled>>,
+    parent_query: Query<&Parent>,
+    mut tile_query: Query<&mut TileVisible>,
+) {
+    for (entity, door) in &door_query {
+        if !key_ring.has(door.0) {
+            continue;
+        }
+        commands.entity(entity).insert(ColliderDisabled);
+        for ancestor in parent_query.iter_ancestors(entity) {
+            if let Ok(mut visible) = tile_query.get_mut(ancestor) {
+                visible.0 = false;
+                break;
+            }
+        }
+    }
+}
+
+/// Press switches as the player walks into them. A switch has to be left
+/// before it can be pressed again.
+fn press_switches(
+    mut commands: Commands,
+    player_query: Query<&GlobalTransform, With<Player>>,
+    mut switch_query: Query<(Entity, &GlobalTransform, &mut Switch)>,
+) {
+    let Ok(player_tf) = player_query.get_single() else {
+        return;
+    };
+
+    for (entity, switch_tf, mut switch) in &mut switch_query {
+        let touching =
+            player_tf.translation().distance(switch_tf.translation()) < SWITCH_PRESS_DISTANCE;
+        if touching == switch.held {
+            continue;
+        }
+        switch.held = touching;
+        if touching {
+            commands.trigger(SwitchPressed(entity));
+        }
+    }
+}
+
+fn on_switch_pressed(
+    trigger: Trigger<SwitchPressed>,
+    mut commands: Commands,
+    mut clock: ResMut<LevelClock>,
+    mut toggled: ResMut<ToggledLayers>,
+    mut switch_query: Query<&mut Switch>,
+) {
+    let Ok(mut switch) = switch_query.get_mut(trigger.event().0) else {
+        return;
+    };
+
+    log::info!("Switch pressed: {:?}", switch.action);
+    switch.on = !switch.on;
+    match &switch.action {
+        SwitchAction::ToggleLayer(layer) => {
+            if !toggled.0.remove(layer) {
+                toggled.0.insert(layer.clone());
+            }
+        }
+        SwitchAction::ReverseRotation => clock.reverse(),
+    }
+    commands.trigger(PlaySfx::Key(SfxKey::ButtonPress));
+}
+
+/// Show or hide the tiles of switched layers, along with their colliders.
+/// This runs again whenever new tile colliders are set up, so that layers
+/// start off in the right state.
+fn update_switched_layers(
+    mut commands: Commands,
+    layout: Option<Res<LevelLayout>>,
+    toggled: Res<ToggledLayers>,
+    new_colliders: Query<(), Added<TileProperties>>,
+    storage_query: Query<(&Name, &TileStorage), With<TiledMapTileLayer>>,
+    children_query: Query<&Children>,
+    collider_query: Query<(), With<Collider>>,
+    mut tile_query: Query<&mut TileVisible>,
+) {
+    let Some(layout) = layout else {
+        return;
+    };
+    if !layout.is_changed() && !toggled.is_changed() && new_colliders.is_empty() {
+        return;
+    }
+
+    for layer in &layout.switched_layers {
+        let visible = layer.start_hidden == toggled.0.contains(&layer.name);
+        // `bevy_ecs_tiled` names each tile layer after the Tiled layer. A layer
+        // using more than one tileset is split across several entities.
+        let layer_name = format!("TiledMapTileLayer({})", layer.name);
+        let storages: Vec<_> = storage_query
+            .iter()
+            .filter(|(name, _)| name.as_str() == layer_name)
+            .map(|(_, storage)| storage)
+            .collect();
+        for cell in &layer.cells {
+            let pos = TilePos::new(cell.x, cell.y);
+            for tile in storages.iter().filter_map(|storage| storage.get(&pos)) {
+                if let Ok(mut tile_visible) = tile_query.get_mut(tile) {
+                    tile_visible.0 = visible;
+                }
+                for collider in children_query
+                    .iter_descendants(tile)
+                    .filter(|&entity| collider_query.contains(entity))
+                {
+                    if visible {
+                        commands.entity(collider).remove::<ColliderDisabled>();
+                    } else {
+                        commands.entity(collider).insert(ColliderDisabled);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,392 @@
+//! Record the player's input every simulation tick, and play it back.
+//!
+//! A [`Replay`] holds the level, the seed for the [`SimulationRng`] and the
+//! input for each tick from the moment the level finished loading. The
+//! simulation is deterministic (see [`super::simulation`]), so feeding the
+//! same input back in reproduces the run exactly.
+//!
+//! Input is quantised to a [`TickInput`] before it is applied, whether it
+//! comes from the player or a replay, so that a live run and its replay see
+//! exactly the same numbers. Each completed level's fastest run is saved,
+//! and can be watched from the level select screen.
+
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    frames::ResetFrameCounter,
+    movement::{record_movement_controller, MovementController},
+    rotation::{record_rotation_controller, RotationController},
+    save::storage,
+    simulation::SimulationRng,
+    spawn::{
+        level::{CurrentLevel, LevelCompleted, LevelMarker, SpawnLevel},
+        player::Player,
+    },
+};
+use crate::{screen::Screen, AppSet};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ReplayRecorder>();
+    app.observe(stop_recording);
+    app.observe(start_recording);
+    app.observe(save_replay);
+    app.add_systems(
+        FixedUpdate,
+        apply_tick_input
+            .after(record_movement_controller)
+            .after(record_rotation_controller)
+            .in_set(AppSet::RecordInput)
+            .run_if(in_state(Screen::Playing)),
+    );
+    app.add_systems(OnExit(Screen::Playing), stop_playback);
+}
+
+/// Bump this whenever a change to the simulation means old replays no
+/// longer play back the same way.
+const REPLAY_VERSION: u32 = 1;
+/// Input axes are stored as whole numbers out of this.
+const AXIS_STEPS: f32 = 127.0;
+
+/// The player's input for a single tick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct TickInput {
+    /// Sideways movement, from -127 (left) to 127 (right).
+    pub move_x: i8,
+    pub jump: bool,
+    /// World rotation, from -127 (clockwise) to 127 (counter-clockwise).
+    pub rotate: i8,
+}
+
+impl TickInput {
+    pub fn new(movement: Vec2, rotation: f32) -> Self {
+        Self {
+            move_x: quantise(movement.x),
+            jump: movement.y > 0.0,
+            rotate: quantise(rotation),
+        }
+    }
+
+    /// The input for [`MovementController`].
+    pub fn movement(&self) -> Vec2 {
+        Vec2::new(
+            self.move_x as f32 / AXIS_STEPS,
+            if self.jump { 1.0 } else { 0.0 },
+        )
+    }
+
+    /// The input for [`RotationController`].
+    pub fn rotation(&self) -> f32 {
+        self.rotate as f32 / AXIS_STEPS
+    }
+}
+
+fn quantise(value: f32) -> i8 {
+    (value.clamp(-1.0, 1.0) * AXIS_STEPS).round() as i8
+}
+
+/// A recorded run through a level.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    /// Index of the level in the campaign.
+    pub level: usize,
+    pub seed: u64,
+    /// The input for every tick, as runs of ticks with the same input.
+    pub inputs: Vec<(u32, TickInput)>,
+}
+
+impl Replay {
+    fn new(level: usize, seed: u64) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            level,
+            seed,
+            inputs: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, input: TickInput) {
+        match self.inputs.last_mut() {
+            Some((ticks, last)) if *last == input => *ticks += 1,
+            _ => self.inputs.push((1, input)),
+        }
+    }
+
+    /// How many ticks the run lasted.
+    pub fn ticks(&self) -> u32 {
+        self.inputs.iter().map(|(ticks, _)| ticks).sum()
+    }
+}
+
+fn storage_name(level: usize) -> String {
+    format!("replay-level{}", level + 1)
+}
+
+/// The saved replay for a level, if there is one that this version of the
+/// game can play back.
+pub fn load_replay(level: usize) -> Option<Replay> {
+    let text = storage::read(&storage_name(level))?;
+    let replay = ron::from_str::<Replay>(&text)
+        .inspect_err(|e| log::warn!("Could not read replay for level {}: {e}", level + 1))
+        .ok()?;
+    (replay.version == REPLAY_VERSION && replay.level == level).then_some(replay)
+}
+
+fn write_replay(replay: &Replay) {
+    match ron::to_string(replay) {
+        Ok(text) => storage::write(&storage_name(replay.level), &text),
+        Err(e) => log::error!("Could not serialize replay: {e}"),
+    }
+}
+
+/// The run being recorded. Empty while a level is loading.
+#[derive(Resource, Debug, Default)]
+struct ReplayRecorder(Option<Replay>);
+
+/// Plays a replay back instead of reading the player's input. Insert this
+/// before entering [`Screen::Playing`], and the level is played from the
+/// replay until it ends.
+#[derive(Resource, Debug)]
+pub struct ReplayPlayback {
+    replay: Replay,
+    /// Which run of inputs is being played.
+    run: usize,
+    /// How many ticks of that run have been played.
+    ticks: u32,
+}
+
+impl ReplayPlayback {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            run: 0,
+            ticks: 0,
+        }
+    }
+
+    pub fn level(&self) -> usize {
+        self.replay.level
+    }
+
+    fn rewind(&mut self) {
+        self.run = 0;
+        self.ticks = 0;
+    }
+
+    /// The input for the next tick, or `None` once the replay is over.
+    fn next(&mut self) -> Option<TickInput> {
+        while let Some(&(ticks, input)) = self.replay.inputs.get(self.run) {
+            if self.ticks < ticks {
+                self.ticks += 1;
+                return Some(input);
+            }
+            self.run += 1;
+            self.ticks = 0;
+        }
+        None
+    }
+}
+
+fn stop_recording(_trigger: Trigger<SpawnLevel>, mut recorder: ResMut<ReplayRecorder>) {
+    recorder.0 = None;
+}
+
+/// Seed the simulation once the level has loaded. A replay brings its own
+/// seed, otherwise a new one is picked and recorded.
+fn start_recording(
+    _trigger: Trigger<ResetFrameCounter>,
+    current_level: Res<CurrentLevel>,
+    mut rng: ResMut<SimulationRng>,
+    mut recorder: ResMut<ReplayRecorder>,
+    playback: Option<ResMut<ReplayPlayback>>,
+) {
+    let seed = match playback {
+        Some(mut playback) => {
+            playback.rewind();
+            playback.replay.seed
+        }
+        None => rand::thread_rng().gen(),
+    };
+    rng.reseed(seed);
+    recorder.0 = Some(Replay::new(current_level.index(), seed));
+}
+
+/// Record this tick's input and hand it to the controllers, or take it from
+/// the replay being played back. Nothing moves while the level is loading.
+fn apply_tick_input(
+    mut recorder: ResMut<ReplayRecorder>,
+    playback: Option<ResMut<ReplayPlayback>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut player_query: Query<&mut MovementController, With<Player>>,
+    mut level_query: Query<&mut RotationController, With<LevelMarker>>,
+) {
+    let input = match (&mut recorder.0, playback) {
+        (None, _) => TickInput::default(),
+        (Some(_), Some(mut playback)) => playback.next().unwrap_or_else(|| {
+            log::warn!("Replay ran out before the level ended");
+            next_screen.set(Screen::LevelSelect);
+            TickInput::default()
+        }),
+        (Some(replay), None) => {
+            let movement = player_query
+                .get_single()
+                .map_or(Vec2::ZERO, |controller| controller.0);
+            let rotation = level_query
+                .get_single()
+                .map_or(0.0, |controller| controller.0);
+            let input = TickInput::new(movement, rotation);
+            replay.push(input);
+            input
+        }
+    };
+
+    for mut controller in &mut player_query {
+        controller.0 = input.movement();
+    }
+    for mut controller in &mut level_query {
+        controller.0 = input.rotation();
+    }
+}
+
+/// Keep the run if it is the fastest one yet. When watching a replay, the
+/// level ending goes back to the level select screen instead.
+fn save_replay(
+    _trigger: Trigger<LevelCompleted>,
+    mut recorder: ResMut<ReplayRecorder>,
+    playback: Option<Res<ReplayPlayback>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    if playback.is_some() {
+        next_screen.set(Screen::LevelSelect);
+        return;
+    }
+    let Some(replay) = recorder.0.take() else {
+        return;
+    };
+
+    let best = load_replay(replay.level).map(|best| best.ticks());
+    if best.is_none_or(|best| replay.ticks() < best) {
+        log::info!(
+            "Saving replay for level {} ({} ticks)",
+            replay.level + 1,
+            replay.ticks()
+        );
+        write_replay(&replay);
+    }
+}
+
+fn stop_playback(mut commands: Commands) {
+    commands.remove_resource::<ReplayPlayback>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(move_x: i8, jump: bool) -> TickInput {
+        TickInput {
+            move_x,
+            jump,
+            rotate: 0,
+        }
+    }
+
+    #[test]
+    fn repeated_input_is_stored_as_runs() {
+        let mut replay = Replay::new(0, 0);
+        for input in [
+            input(0, false),
+            input(0, false),
+            input(127, false),
+            input(127, true),
+            input(127, true),
+            input(127, true),
+            input(0, false),
+        ] {
+            replay.push(input);
+        }
+
+        assert_eq!(
+            replay.inputs,
+            vec![
+                (2, input(0, false)),
+                (1, input(127, false)),
+                (3, input(127, true)),
+                (1, input(0, false)),
+            ]
+        );
+        assert_eq!(replay.ticks(), 7);
+    }
+
+    #[test]
+    fn playback_returns_every_recorded_tick() {
+        let recorded = [
+            input(0, false),
+            input(-127, false),
+            input(-127, false),
+            input(-127, true),
+            input(50, true),
+            input(50, true),
+        ];
+        let mut replay = Replay::new(0, 0);
+        for input in recorded {
+            replay.push(input);
+        }
+
+        let mut playback = ReplayPlayback::new(replay);
+        let played: Vec<_> = std::iter::from_fn(|| playback.next()).collect();
+        assert_eq!(played, recorded);
+        assert_eq!(playback.next(), None);
+
+        playback.rewind();
+        assert_eq!(playback.next(), Some(recorded[0]));
+    }
+
+    #[test]
+    fn empty_replays_play_nothing() {
+        let mut playback = ReplayPlayback::new(Replay::new(0, 0));
+        assert_eq!(playback.next(), None);
+    }
+
+    #[test]
+    fn input_is_quantised() {
+        let input = TickInput::new(Vec2::new(0.5, 1.0), -1.0);
+        assert_eq!(
+            input,
+            TickInput {
+                move_x: 64,
+                jump: true,
+                rotate: -127,
+            }
+        );
+        assert_eq!(input.movement(), Vec2::new(64.0 / 127.0, 1.0));
+        assert_eq!(input.rotation(), -1.0);
+
+        // Out of range input is clamped, and only upwards input jumps.
+        let input = TickInput::new(Vec2::new(2.0, -1.0), -3.0);
+        assert_eq!(
+            input,
+            TickInput {
+                move_x: 127,
+                jump: false,
+                rotate: -127,
+            }
+        );
+    }
+
+    #[test]
+    fn quantised_input_is_unchanged_by_quantising_again() {
+        for step in -127..=127 {
+            for jump in [false, true] {
+                let input = TickInput {
+                    move_x: step,
+                    jump,
+                    rotate: step,
+                };
+                assert_eq!(TickInput::new(input.movement(), input.rotation()), input);
+            }
+        }
+    }
+}
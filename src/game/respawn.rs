@@ -0,0 +1,236 @@
+//! Respawn the player when they fall out of the level.
+//!
+//! The level bounds come from the size of the map, and are checked in the
+//! level's own (rotated) space so that they turn along with the world.
+//! Anything else that should send the player back, like hazards, can trigger
+//! [`RespawnPlayer`].
+//!
+//! The player moves back after a fixed number of simulation ticks rather than
+//! when the fade finishes, so that respawns happen at the same point in a
+//! replay. The respawn fade has its own overlay, so it never gets mixed up
+//! with the fades between levels, and players only respawn while the level
+//! is [`LevelState::Active`].
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use super::{
+    conga::CongaLine,
+    gravity::{GravityFrame, GravityMode},
+    movement::{PreviousPhysicalTranslation, VisualTranslation},
+    score::{Score, UpdateScore},
+    simulation::TICK_SECS,
+    spawn::{
+        level::{CurrentLevel, LevelLayout, LevelMarker, LevelState, Levels, PlayerSpawnPoint},
+        player::{IsOnGround, Player, Velocity},
+    },
+};
+use crate::AppSet;
+
+pub(super) fn plugin(app: &mut App) {
+    app.observe(on_respawn_player);
+    app.add_systems(Startup, spawn_respawn_overlay);
+    app.add_systems(
+        FixedUpdate,
+        (
+            tick_respawning.in_set(AppSet::TickTimers),
+            (
+                check_out_of_bounds.run_if(in_state(LevelState::Active)),
+                respawn_player,
+            )
+                .in_set(AppSet::Update),
+        ),
+    );
+    app.add_systems(Update, update_respawn_overlay.in_set(AppSet::Update));
+}
+
+/// How far outside the map, in pixels, the player can go before they respawn.
+const OUT_OF_BOUNDS_MARGIN: f32 = 64.0;
+/// How long the screen takes to fade out and back in again, in seconds.
+const FADE_SECS: f32 = 0.25;
+
+/// Marks a player who is respawning. The timer runs while the screen fades
+/// out and back in again, and the player moves halfway through.
+#[derive(Component, Debug)]
+struct Respawning {
+    timer: Timer,
+    moved: bool,
+}
+
+impl Respawning {
+    fn new() -> Self {
+        Self {
+            timer: Timer::from_seconds(FADE_SECS * 2.0, TimerMode::Once),
+            moved: false,
+        }
+    }
+
+    /// How dark the screen is, from 0 to 1.
+    fn darkness(&self) -> f32 {
+        1.0 - ((self.timer.elapsed_secs() - FADE_SECS) / FADE_SECS).abs()
+    }
+}
+
+/// Covers the screen while the player respawns.
+#[derive(Component, Debug)]
+struct RespawnOverlay;
+
+/// Send the player back to the spawn point. This counts as a death.
+/// Ignored unless the level is [`LevelState::Active`].
+#[derive(Event, Debug)]
+pub struct RespawnPlayer;
+
+fn on_respawn_player(
+    _trigger: Trigger<RespawnPlayer>,
+    mut commands: Commands,
+    state: Res<State<LevelState>>,
+    mut score: ResMut<Score>,
+    player_query: Query<Entity, (With<Player>, Without<Respawning>)>,
+) {
+    if *state.get() != LevelState::Active {
+        return;
+    }
+
+    for entity in &player_query {
+        score.deaths += 1;
+        commands.entity(entity).insert(Respawning::new());
+        commands.trigger(UpdateScore);
+    }
+}
+
+fn tick_respawning(mut query: Query<&mut Respawning>) {
+    for mut respawning in &mut query {
+        respawning.timer.tick(Duration::from_secs_f32(TICK_SECS));
+    }
+}
+
+fn spawn_respawn_overlay(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Respawn Overlay"),
+        NodeBundle {
+            style: Style {
+                left: Val::Px(0.0),
+                right: Val::Px(0.0),
+                top: Val::Px(0.0),
+                bottom: Val::Px(0.0),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            background_color: Color::BLACK.with_alpha(0.0).into(),
+            ..default()
+        },
+        RespawnOverlay,
+    ));
+}
+
+fn update_respawn_overlay(
+    player_query: Query<&Respawning, With<Player>>,
+    mut overlay_query: Query<&mut BackgroundColor, With<RespawnOverlay>>,
+) {
+    let darkness = player_query
+        .iter()
+        .map(Respawning::darkness)
+        .fold(0.0, f32::max);
+    for mut color in &mut overlay_query {
+        color.0.set_alpha(darkness);
+    }
+}
+
+fn check_out_of_bounds(
+    mut commands: Commands,
+    layout: Option<Res<LevelLayout>>,
+    level_query: Query<&Transform, (With<LevelMarker>, Without<Player>)>,
+    player_query: Query<&Transform, (With<Player>, Without<Respawning>)>,
+) {
+    let Some(layout) = layout else {
+        return;
+    };
+    let Ok(level_transform) = level_query.get_single() else {
+        return;
+    };
+
+    let bounds = layout.bounds().inflate(OUT_OF_BOUNDS_MARGIN);
+    for transform in &player_query {
+        let offset = transform.translation - level_transform.translation;
+        let level_pos = level_transform.rotation.inverse() * offset;
+        if bounds.contains(level_pos.truncate()) {
+            continue;
+        }
+
+        log::info!("Player left the level at {level_pos}, respawning");
+        commands.trigger(RespawnPlayer);
+    }
+}
+
+/// Move the player back to the spawn point once the screen has faded out, and
+/// stop respawning once it has faded back in. The level keeps rotating, so the
+/// spawn point is converted to world space.
+fn respawn_player(
+    mut commands: Commands,
+    levels: Res<Levels>,
+    current_level: Res<CurrentLevel>,
+    level_query: Query<&Transform, (With<LevelMarker>, Without<Player>)>,
+    spawn_point_query: Query<&Transform, (With<PlayerSpawnPoint>, Without<Player>)>,
+    mut player_query: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut Velocity,
+            &mut IsOnGround,
+            &mut GravityFrame,
+            &mut PreviousPhysicalTranslation,
+            &mut VisualTranslation,
+            &mut CongaLine,
+            &mut Respawning,
+        ),
+        With<Player>,
+    >,
+) {
+    let (Ok(level_transform), Ok(spawn_point)) =
+        (level_query.get_single(), spawn_point_query.get_single())
+    else {
+        return;
+    };
+
+    let spawn_pos = level_transform.transform_point(spawn_point.translation);
+    // Start off the right way up for the level's gravity.
+    let up = match levels
+        .current(*current_level)
+        .map(|level| level.gravity_mode)
+        .unwrap_or_default()
+    {
+        GravityMode::World => Vec2::Y,
+        GravityMode::Level | GravityMode::Surface => {
+            (level_transform.rotation * Vec3::Y).truncate()
+        }
+    };
+    for (
+        entity,
+        mut transform,
+        mut velocity,
+        mut is_on_ground,
+        mut frame,
+        mut previous,
+        mut visual,
+        mut conga_line,
+        mut respawning,
+    ) in &mut player_query
+    {
+        if respawning.timer.finished() {
+            commands.entity(entity).remove::<Respawning>();
+        }
+        if respawning.moved || respawning.timer.elapsed_secs() < FADE_SECS {
+            continue;
+        }
+
+        respawning.moved = true;
+        transform.translation = spawn_pos;
+        *velocity = Velocity::default();
+        is_on_ground.is_on_ground = false;
+        frame.up = up;
+        previous.0 = spawn_pos.truncate();
+        visual.0 = spawn_pos.truncate();
+        conga_line.reset_trail(spawn_pos.truncate());
+    }
+}
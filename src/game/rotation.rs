@@ -0,0 +1,399 @@
+//! How the world rotates over the course of a level.
+//!
+//! Each level has a [`RotationProfile`] which gives the world angle at any
+//! point in time. The angle is always computed from the [`LevelClock`], which
+//! counts simulation ticks, rather than accumulated each tick, so a level
+//! plays out the same way every time.
+//! Switches can reverse the rotation, which runs the clock backwards.
+//!
+//! Levels can also hand some or all of the rotation over to the player, see
+//! [`RotationMode`]. Player input is recorded into a [`RotationController`]
+//! on the level, and turned into rotation with some inertia.
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::{
+    frames::ResetFrameCounter,
+    input::{Action, ActionInput},
+    simulation::TICK_SECS,
+};
+use crate::AppSet;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<LevelClock>();
+    app.observe(reset_level_clock);
+
+    app.register_type::<RotationController>();
+    app.register_type::<PlayerRotation>();
+    app.add_systems(
+        FixedUpdate,
+        record_rotation_controller.in_set(AppSet::RecordInput),
+    );
+}
+
+/// How far into its [`RotationProfile`] the current level is, in simulation
+/// ticks. This is ticked alongside the world rotation in `FixedUpdate`, so it
+/// stops whenever physics does.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct LevelClock {
+    ticks: i64,
+    reversed: bool,
+}
+
+impl LevelClock {
+    pub fn tick(&mut self) {
+        self.ticks += if self.reversed { -1 } else { 1 };
+    }
+
+    /// Seconds into the rotation profile. This goes back down while the
+    /// rotation is reversed, and can go below zero.
+    pub fn elapsed_secs(&self) -> f32 {
+        self.ticks as f32 * TICK_SECS
+    }
+
+    /// Play the rotation profile backwards from here, or forwards again.
+    pub fn reverse(&mut self) {
+        self.reversed = !self.reversed;
+    }
+}
+
+/// Start the clock once the level has finished loading, rather than when it
+/// is spawned, so that however long the map takes to load the level starts
+/// at the same angle.
+fn reset_level_clock(_trigger: Trigger<ResetFrameCounter>, mut clock: ResMut<LevelClock>) {
+    *clock = LevelClock::default();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect, Deserialize)]
+pub enum RotationDirection {
+    Clockwise,
+    #[default]
+    CounterClockwise,
+}
+
+impl RotationDirection {
+    fn sign(self) -> f32 {
+        match self {
+            RotationDirection::Clockwise => -1.0,
+            RotationDirection::CounterClockwise => 1.0,
+        }
+    }
+}
+
+/// All angles are in degrees and all times are in seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, Deserialize)]
+pub enum RotationProfile {
+    /// Rotate at a constant speed.
+    Constant {
+        speed: f32,
+        #[serde(default)]
+        direction: RotationDirection,
+    },
+    /// Swing back and forth either side of the starting angle.
+    Oscillating { amplitude: f32, period: f32 },
+    /// Turn a quarter of the way around in `turn_time`, then wait for `pause`.
+    Stepwise {
+        turn_time: f32,
+        pause: f32,
+        #[serde(default)]
+        direction: RotationDirection,
+    },
+    /// Start at `speed` and speed up by `acceleration` every second until
+    /// reaching `max_speed`.
+    Accelerating {
+        speed: f32,
+        acceleration: f32,
+        max_speed: f32,
+        #[serde(default)]
+        direction: RotationDirection,
+    },
+}
+
+impl Default for RotationProfile {
+    fn default() -> Self {
+        RotationProfile::Constant {
+            speed: 5.0,
+            direction: RotationDirection::CounterClockwise,
+        }
+    }
+}
+
+impl RotationProfile {
+    /// The world angle `time` seconds into the level.
+    pub fn angle(&self, time: f32) -> f32 {
+        match *self {
+            RotationProfile::Constant { speed, direction } => direction.sign() * speed * time,
+            RotationProfile::Oscillating { amplitude, period } => {
+                if period <= 0.0 {
+                    return 0.0;
+                }
+                amplitude * (std::f32::consts::TAU * time / period).sin()
+            }
+            RotationProfile::Stepwise {
+                turn_time,
+                pause,
+                direction,
+            } => {
+                let cycle = turn_time + pause;
+                if cycle <= 0.0 {
+                    return 0.0;
+                }
+                let steps = (time / cycle).floor();
+                let into_step = time - steps * cycle;
+                let turned = if turn_time > 0.0 {
+                    (into_step / turn_time).min(1.0)
+                } else {
+                    1.0
+                };
+                direction.sign() * 90.0 * (steps + turned)
+            }
+            RotationProfile::Accelerating {
+                speed,
+                acceleration,
+                max_speed,
+                direction,
+            } => {
+                let time_to_max = if acceleration > 0.0 {
+                    ((max_speed - speed) / acceleration).max(0.0)
+                } else {
+                    f32::INFINITY
+                };
+                // Reversing past the start speeds up again the other way.
+                let accelerating = time.abs().min(time_to_max);
+                let cruising = time.abs() - accelerating;
+                let angle = speed * accelerating
+                    + 0.5 * acceleration.max(0.0) * accelerating * accelerating
+                    + max_speed * cruising;
+                direction.sign() * time.signum() * angle
+            }
+        }
+    }
+}
+
+/// Who gets to rotate the world during a level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect, Deserialize)]
+pub enum RotationMode {
+    /// The world rotates by itself following the level's [`RotationProfile`].
+    #[default]
+    Passive,
+    /// The world only rotates when the player turns it.
+    Player,
+    /// The world follows the [`RotationProfile`] and the player can turn it on top of that.
+    Hybrid,
+}
+
+impl RotationMode {
+    pub fn is_passive(self) -> bool {
+        matches!(self, RotationMode::Passive | RotationMode::Hybrid)
+    }
+
+    pub fn is_player_driven(self) -> bool {
+        matches!(self, RotationMode::Player | RotationMode::Hybrid)
+    }
+}
+
+/// The player's rotation input, between -1 and 1. Positive values turn the
+/// world counter-clockwise.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct RotationController(pub f32);
+
+/// Turns [`RotationController`] input into rotation. All angles are in degrees.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct PlayerRotation {
+    /// How quickly the rotation speeds up and slows down, per second.
+    pub acceleration: f32,
+    /// The fastest the player can turn the world, per second.
+    pub max_speed: f32,
+    /// Current rotation speed, per second.
+    pub velocity: f32,
+    /// How far the player has turned the world so far.
+    pub angle: f32,
+}
+
+impl Default for PlayerRotation {
+    fn default() -> Self {
+        Self {
+            acceleration: 180.0,
+            max_speed: 90.0,
+            velocity: 0.0,
+            angle: 0.0,
+        }
+    }
+}
+
+impl PlayerRotation {
+    /// Advance the rotation by `delta` seconds with the given input.
+    pub fn update(&mut self, input: f32, delta: f32) {
+        let target = input.clamp(-1.0, 1.0) * self.max_speed;
+        let max_change = self.acceleration * delta;
+        self.velocity += (target - self.velocity).clamp(-max_change, max_change);
+        self.angle += self.velocity * delta;
+    }
+}
+
+pub fn record_rotation_controller(
+    input: ActionInput,
+    mut controller_query: Query<&mut RotationController>,
+) {
+    let mut intent = 0.0;
+    if input.pressed(Action::RotateCounterClockwise) {
+        intent += 1.0;
+    }
+    if input.pressed(Action::RotateClockwise) {
+        intent -= 1.0;
+    }
+
+    for mut controller in &mut controller_query {
+        controller.0 = intent;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_clock_runs_backwards_when_reversed() {
+        let mut clock = LevelClock::default();
+        for _ in 0..3 {
+            clock.tick();
+        }
+        assert_eq!(clock.elapsed_secs(), 3.0 * TICK_SECS);
+
+        clock.reverse();
+        for _ in 0..5 {
+            clock.tick();
+        }
+        assert_eq!(clock.elapsed_secs(), -2.0 * TICK_SECS);
+
+        clock.reverse();
+        clock.tick();
+        assert_eq!(clock.elapsed_secs(), -TICK_SECS);
+    }
+
+    fn assert_angle(profile: RotationProfile, time: f32, expected: f32) {
+        let angle = profile.angle(time);
+        assert!(
+            (angle - expected).abs() < 1e-3,
+            "{profile:?} at {time}s: expected {expected}, got {angle}"
+        );
+    }
+
+    #[test]
+    fn constant() {
+        let profile = RotationProfile::Constant {
+            speed: 5.0,
+            direction: RotationDirection::CounterClockwise,
+        };
+        assert_angle(profile, 0.0, 0.0);
+        assert_angle(profile, 2.0, 10.0);
+        assert_angle(profile, -2.0, -10.0);
+
+        let profile = RotationProfile::Constant {
+            speed: 5.0,
+            direction: RotationDirection::Clockwise,
+        };
+        assert_angle(profile, 2.0, -10.0);
+        assert_angle(profile, -2.0, 10.0);
+    }
+
+    #[test]
+    fn oscillating() {
+        let profile = RotationProfile::Oscillating {
+            amplitude: 30.0,
+            period: 4.0,
+        };
+        assert_angle(profile, 0.0, 0.0);
+        assert_angle(profile, 1.0, 30.0);
+        assert_angle(profile, 2.0, 0.0);
+        assert_angle(profile, 3.0, -30.0);
+        assert_angle(profile, -1.0, -30.0);
+    }
+
+    #[test]
+    fn oscillating_without_a_period_stays_still() {
+        let profile = RotationProfile::Oscillating {
+            amplitude: 30.0,
+            period: 0.0,
+        };
+        assert_angle(profile, 1.0, 0.0);
+        assert_angle(profile, -1.0, 0.0);
+    }
+
+    #[test]
+    fn stepwise() {
+        let profile = RotationProfile::Stepwise {
+            turn_time: 2.0,
+            pause: 6.0,
+            direction: RotationDirection::CounterClockwise,
+        };
+        assert_angle(profile, 0.0, 0.0);
+        assert_angle(profile, 1.0, 45.0);
+        assert_angle(profile, 2.0, 90.0);
+        assert_angle(profile, 5.0, 90.0);
+        assert_angle(profile, 9.0, 135.0);
+        // Running backwards, the pause comes first.
+        assert_angle(profile, -1.0, 0.0);
+        assert_angle(profile, -7.0, -45.0);
+        assert_angle(profile, -8.0, -90.0);
+
+        let profile = RotationProfile::Stepwise {
+            turn_time: 2.0,
+            pause: 6.0,
+            direction: RotationDirection::Clockwise,
+        };
+        assert_angle(profile, 1.0, -45.0);
+        assert_angle(profile, -7.0, 45.0);
+    }
+
+    #[test]
+    fn stepwise_without_a_turn_time_snaps() {
+        let profile = RotationProfile::Stepwise {
+            turn_time: 0.0,
+            pause: 2.0,
+            direction: RotationDirection::CounterClockwise,
+        };
+        assert_angle(profile, 1.0, 90.0);
+        assert_angle(profile, 3.0, 180.0);
+        assert_angle(profile, -1.0, 0.0);
+    }
+
+    #[test]
+    fn accelerating() {
+        let profile = RotationProfile::Accelerating {
+            speed: 5.0,
+            acceleration: 1.0,
+            max_speed: 10.0,
+            direction: RotationDirection::CounterClockwise,
+        };
+        assert_angle(profile, 0.0, 0.0);
+        assert_angle(profile, 2.0, 12.0);
+        // Reaches max speed after 5 seconds, having turned 37.5 degrees.
+        assert_angle(profile, 7.0, 57.5);
+        assert_angle(profile, -2.0, -12.0);
+        assert_angle(profile, -7.0, -57.5);
+
+        let profile = RotationProfile::Accelerating {
+            speed: 5.0,
+            acceleration: 1.0,
+            max_speed: 10.0,
+            direction: RotationDirection::Clockwise,
+        };
+        assert_angle(profile, 2.0, -12.0);
+    }
+
+    #[test]
+    fn accelerating_without_acceleration_is_constant() {
+        let profile = RotationProfile::Accelerating {
+            speed: 5.0,
+            acceleration: 0.0,
+            max_speed: 10.0,
+            direction: RotationDirection::CounterClockwise,
+        };
+        assert_angle(profile, 3.0, 15.0);
+        assert_angle(profile, -3.0, -15.0);
+    }
+}
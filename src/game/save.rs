@@ -0,0 +1,302 @@
+//! Persist settings, controls and progress between sessions.
+//!
+//! Save data is stored as RON in the platform config directory on native,
+//! and in `localStorage` on web. Replays (see [`super::replay`]) are stored
+//! alongside it.
+//!
+//! A save that can't be read, or that was written by a newer version of the
+//! game, falls back to the defaults rather than stopping the game from
+//! starting. The original is copied to a backup first so that it isn't lost
+//! when the defaults are saved over it.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{
+    input::InputMap, progress::Progress, settings::GameSettings, simulation::TICKS_PER_SECOND,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<LastSave>();
+    app.add_systems(PreStartup, load_save_data);
+    app.add_systems(
+        Last,
+        write_save_data.run_if(
+            resource_changed::<GameSettings>
+                .or_else(resource_changed::<InputMap>)
+                .or_else(resource_changed::<Progress>),
+        ),
+    );
+}
+
+/// Bump this whenever [`SaveData`] changes in a way that `#[serde(default)]`
+/// can't cope with, and add a step to [`migrate`].
+const SAVE_VERSION: u32 = 2;
+/// What the save data is stored as, see [`storage`].
+const SAVE_NAME: &str = "save";
+/// Where a save that couldn't be loaded is copied to.
+const BACKUP_NAME: &str = "save-backup";
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaveData {
+    pub version: u32,
+    pub settings: GameSettings,
+    pub input_map: InputMap,
+    pub progress: Progress,
+}
+
+/// Just enough of the save to tell which version it was written with.
+#[derive(Deserialize)]
+struct SaveHeader {
+    #[serde(default)]
+    version: u32,
+}
+
+#[derive(Debug, Error)]
+enum SaveError {
+    #[error("Could not parse save data: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("Save data version {0} is newer than this game supports")]
+    TooNew(u32),
+}
+
+impl SaveData {
+    fn parse(text: &str) -> Result<Self, SaveError> {
+        let header: SaveHeader = ron::from_str(text)?;
+        let data = migrate(header.version, text)?;
+        Ok(Self {
+            version: SAVE_VERSION,
+            ..data
+        })
+    }
+}
+
+/// Read a save written by any version of the game.
+///
+/// Version 0 is a save without a version number. Every field has a default,
+/// so fields added since are filled in.
+///
+/// - Version 2 keeps best level times in simulation ticks instead of seconds.
+///
+/// A newer game may have moved things around in ways this one can't know
+/// about, so newer saves aren't read at all.
+fn migrate(version: u32, text: &str) -> Result<SaveData, SaveError> {
+    match version {
+        0..=1 => {
+            let mut data: SaveData = ron::from_str(text)?;
+            let old: SaveDataV1 = ron::from_str(text)?;
+            for (index, level) in old.progress.levels.iter().enumerate() {
+                data.progress.record_mut(index).best_ticks = level
+                    .best_time
+                    .map(|secs| (secs as f64 * TICKS_PER_SECOND).round() as u32);
+            }
+            Ok(data)
+        }
+        SAVE_VERSION => Ok(ron::from_str(text)?),
+        _ => Err(SaveError::TooNew(version)),
+    }
+}
+
+/// The parts of a version 1 save that have changed since.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct SaveDataV1 {
+    progress: ProgressV1,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ProgressV1 {
+    levels: Vec<LevelRecordV1>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct LevelRecordV1 {
+    /// Fastest completion time in seconds.
+    best_time: Option<f32>,
+}
+
+/// The save data as last read or written, so that it is only written again
+/// once something has actually changed.
+#[derive(Resource, Debug, Default)]
+struct LastSave(String);
+
+fn load_save_data(mut commands: Commands, mut last_save: ResMut<LastSave>) {
+    let save_data = match storage::read(SAVE_NAME) {
+        Some(text) => match SaveData::parse(&text) {
+            Ok(save_data) => {
+                last_save.0 = text;
+                save_data
+            }
+            Err(e) => {
+                log::warn!("{e}. Using defaults, and keeping a copy as {BACKUP_NAME}");
+                storage::write(BACKUP_NAME, &text);
+                SaveData::default()
+            }
+        },
+        None => SaveData::default(),
+    };
+
+    commands.insert_resource(save_data.settings);
+    commands.insert_resource(save_data.input_map);
+    commands.insert_resource(save_data.progress);
+}
+
+fn write_save_data(
+    settings: Res<GameSettings>,
+    input_map: Res<InputMap>,
+    progress: Res<Progress>,
+    mut last_save: ResMut<LastSave>,
+) {
+    let save_data = SaveData {
+        version: SAVE_VERSION,
+        settings: *settings,
+        input_map: input_map.clone(),
+        progress: progress.clone(),
+    };
+
+    match ron::ser::to_string_pretty(&save_data, default()) {
+        Ok(text) if text == last_save.0 => {}
+        Ok(text) => {
+            storage::write(SAVE_NAME, &text);
+            last_save.0 = text;
+        }
+        Err(e) => log::error!("Could not serialize save data: {e}"),
+    }
+}
+
+/// Reads and writes named blobs of text. On native each name is a `.ron`
+/// file in the config directory, and on web it is a `localStorage` key.
+#[cfg(not(target_family = "wasm"))]
+pub(super) mod storage {
+    use std::{fs, path::PathBuf};
+
+    use directories::ProjectDirs;
+
+    fn save_path(name: &str) -> Option<PathBuf> {
+        let dirs = ProjectDirs::from("com", "stevepryde", "Dizzy Ducklings")?;
+        Some(dirs.config_dir().join(format!("{name}.ron")))
+    }
+
+    pub fn read(name: &str) -> Option<String> {
+        fs::read_to_string(save_path(name)?).ok()
+    }
+
+    pub fn write(name: &str, text: &str) {
+        let Some(path) = save_path(name) else {
+            log::error!("Could not find a config directory to save to");
+            return;
+        };
+
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&path, text));
+        if let Err(e) = result {
+            log::error!("Could not write {name} to {}: {e}", path.display());
+        }
+    }
+}
+
+#[cfg(target_family = "wasm")]
+pub(super) mod storage {
+    fn storage_key(name: &str) -> String {
+        format!("dizzy-ducklings-{name}")
+    }
+
+    fn local_storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+
+    pub fn read(name: &str) -> Option<String> {
+        local_storage()?.get_item(&storage_key(name)).ok()?
+    }
+
+    pub fn write(name: &str, text: &str) {
+        let Some(storage) = local_storage() else {
+            log::error!("localStorage is not available");
+            return;
+        };
+
+        if storage.set_item(&storage_key(name), text).is_err() {
+            log::error!("Could not write {name} to localStorage");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::input::{Action, Binding};
+
+    #[test]
+    fn saves_are_read_back() {
+        let mut save_data = SaveData {
+            version: SAVE_VERSION,
+            ..default()
+        };
+        save_data.settings.sound_enabled = false;
+        save_data
+            .input_map
+            .rebind(Action::Jump, Binding::Key(KeyCode::KeyW));
+
+        let text = ron::ser::to_string_pretty(&save_data, default()).unwrap();
+        assert_eq!(SaveData::parse(&text).unwrap(), save_data);
+    }
+
+    #[test]
+    fn saves_without_a_version_are_read() {
+        let text = "(settings: (sound_enabled: false), progress: (levels: [(completed: true)]))";
+        let save_data = SaveData::parse(text).unwrap();
+
+        assert_eq!(save_data.version, SAVE_VERSION);
+        assert!(!save_data.settings.sound_enabled);
+        assert_eq!(save_data.input_map, InputMap::default());
+        assert!(save_data.progress.record(0).completed);
+        assert!(!save_data.progress.record(1).completed);
+    }
+
+    #[test]
+    fn best_times_are_converted_to_ticks() {
+        let text = "(
+            version: 1,
+            progress: (levels: [
+                (completed: true, best_time: Some(2.5)),
+                (completed: true, best_score: 40),
+            ]),
+        )";
+        let save_data = SaveData::parse(text).unwrap();
+
+        let record = save_data.progress.record(0);
+        assert!(record.completed);
+        assert_eq!(record.best_ticks, Some(160));
+        let record = save_data.progress.record(1);
+        assert_eq!(record.best_ticks, None);
+        assert_eq!(record.best_score, 40);
+    }
+
+    #[test]
+    fn newer_saves_are_not_read() {
+        let newer = SAVE_VERSION + 1;
+        let text = format!("(version: {newer}, settings: (sound_enabled: false))");
+        assert!(matches!(
+            SaveData::parse(&text),
+            Err(SaveError::TooNew(version)) if version == newer
+        ));
+    }
+
+    #[test]
+    fn corrupt_saves_are_not_read() {
+        assert!(matches!(
+            SaveData::parse("(settings: (sound_enabled: maybe"),
+            Err(SaveError::Ron(_))
+        ));
+        assert!(matches!(
+            migrate(SAVE_VERSION, "(progress: 12)"),
+            Err(SaveError::Ron(_))
+        ));
+    }
+}
@@ -2,15 +2,32 @@ use std::time::Duration;
 
 use bevy::{prelude::*, time::Stopwatch};
 
-use crate::{game::spawn::level::EndLevel, AppSet};
+use crate::{
+    game::spawn::level::{EndLevel, LevelCompleted, LevelFinishPoint},
+    screen::Screen,
+    AppSet,
+};
 
 use super::{
+    ai::DucklingBrain,
     audio::sfx::PlaySfx,
-    spawn::{duckling::Duckling, level::SpawnLevel},
+    conga::{CollectCooldown, Following, JoinCongaLine},
+    spawn::{
+        duckling::{Duckling, DucklingKind},
+        level::SpawnLevel,
+        player::{Player, Velocity},
+    },
 };
 
 const SCORE_COLOR: Color = Color::linear_rgb(0.8, 0.8, 0.4);
 
+/// Upwards speed given to the player when they collect a bouncy duckling.
+const BOUNCE_SPEED: f32 = 500.0;
+/// How long the points for a collected duckling stay on screen, in seconds.
+const POPUP_SECS: f32 = 0.8;
+/// How fast the points for a collected duckling float upwards, in pixels per second.
+const POPUP_RISE_SPEED: f32 = 60.0;
+
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<Score>();
     app.init_resource::<OverallScore>();
@@ -20,12 +37,17 @@ pub(super) fn plugin(app: &mut App) {
     app.observe(on_end_level);
     app.observe(carry_over_stopwatch);
     app.observe(resume_stopwatch);
-    app.add_systems(Update, update_stopwatch.in_set(AppSet::Update));
+    app.add_systems(
+        Update,
+        (update_stopwatch, update_score_popups).in_set(AppSet::Update),
+    );
 }
 
 #[derive(Resource, Clone, Debug, Default)]
 pub struct OverallScore {
     pub total_seconds: f32,
+    /// Whether this run started from the first level.
+    pub full_run: bool,
 }
 
 #[derive(Resource, Clone, Debug)]
@@ -33,6 +55,8 @@ pub struct Score {
     pub score: u32,
     pub ducklings_total: u32,
     pub ducklings_collected: u32,
+    /// How many times the player has fallen out of the level.
+    pub deaths: u32,
     pub stopwatch: Stopwatch,
 }
 
@@ -43,6 +67,7 @@ impl Default for Score {
             // Prevent game from ending immediately.
             ducklings_total: 100,
             ducklings_collected: 0,
+            deaths: 0,
             stopwatch: Stopwatch::new(),
         }
     }
@@ -55,26 +80,91 @@ fn on_duckling_collected(
     trigger: Trigger<DucklingCollected>,
     mut score: ResMut<Score>,
     mut commands: Commands,
-    ducklings: Query<&Duckling>,
+    ducklings: Query<
+        (&DucklingKind, &GlobalTransform, Option<&DucklingBrain>),
+        (With<Duckling>, Without<Following>, Without<CollectCooldown>),
+    >,
+    mut player_query: Query<&mut Velocity, With<Player>>,
+    exits: Query<(), With<LevelFinishPoint>>,
 ) {
-    if ducklings.get(trigger.event().0).is_ok() {
+    if let Ok((kind, transform, brain)) = ducklings.get(trigger.event().0) {
+        // Sleeping ducklings stay where they are.
+        if brain.is_some_and(DucklingBrain::is_asleep) {
+            return;
+        }
+
         score.ducklings_collected += 1;
-        commands.entity(trigger.event().0).despawn_recursive();
+        score.score += kind.points();
+        commands.trigger(JoinCongaLine(trigger.event().0));
         commands.trigger(UpdateScore);
         commands.trigger(PlaySfx::CollectDuckling);
 
-        if score.ducklings_collected == score.ducklings_total {
+        if *kind == DucklingKind::Bouncy {
+            for mut velocity in &mut player_query {
+                velocity.y = velocity.y.max(BOUNCE_SPEED);
+            }
+        }
+        spawn_score_popup(&mut commands, *kind, transform.translation());
+
+        // Levels with an exit end when the player reaches it instead.
+        if score.ducklings_collected == score.ducklings_total && exits.is_empty() {
+            commands.trigger(LevelCompleted);
             commands.trigger(EndLevel);
         }
     }
 }
 
+/// Points shown briefly where a duckling was collected.
+#[derive(Component, Debug)]
+struct ScorePopup(Timer);
+
+fn spawn_score_popup(commands: &mut Commands, kind: DucklingKind, position: Vec3) {
+    commands.spawn((
+        Name::new("Score Popup"),
+        ScorePopup(Timer::from_seconds(POPUP_SECS, TimerMode::Once)),
+        Text2dBundle {
+            text: Text::from_section(
+                format!("+{}", kind.points()),
+                TextStyle {
+                    font_size: 20.0,
+                    color: kind.color(),
+                    ..default()
+                },
+            ),
+            transform: Transform::from_translation(position.truncate().extend(10.0)),
+            ..default()
+        },
+        StateScoped(Screen::Playing),
+    ));
+}
+
+fn update_score_popups(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut ScorePopup, &mut Transform, &mut Text)>,
+) {
+    for (entity, mut popup, mut transform, mut text) in &mut query {
+        if popup.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        transform.translation.y += POPUP_RISE_SPEED * time.delta_seconds();
+        let alpha = 1.0 - popup.0.fraction();
+        for section in &mut text.sections {
+            section.style.color.set_alpha(alpha);
+        }
+    }
+}
+
 #[derive(Component, Debug, Clone, Copy, PartialEq, Default)]
 pub struct ScoreMarker;
 
 #[derive(Component, Debug, Clone, Copy, PartialEq, Default)]
 pub struct StopwatchMarker;
 
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default)]
+pub struct DeathsMarker;
+
 #[derive(Event, Debug)]
 pub struct UpdateScore;
 
@@ -82,13 +172,17 @@ fn on_update_score(
     _trigger: Trigger<UpdateScore>,
     score: Res<Score>,
     mut text_query: Query<(Entity, &ScoreMarker, &mut Text)>,
+    mut deaths_query: Query<&mut Text, (With<DeathsMarker>, Without<ScoreMarker>)>,
 ) {
     for (_, _, mut text) in text_query.iter_mut() {
         text.sections[0].value = format!(
-            "Ducklings collected: {} / {}",
-            score.ducklings_collected, score.ducklings_total
+            "Ducklings collected: {} / {}  Score: {}",
+            score.ducklings_collected, score.ducklings_total, score.score
         );
     }
+    for mut text in deaths_query.iter_mut() {
+        text.sections[0].value = format!("Deaths: {}", score.deaths);
+    }
 }
 
 fn update_stopwatch(
@@ -103,7 +197,16 @@ fn update_stopwatch(
     }
 }
 
-fn on_start_level(_trigger: Trigger<SpawnLevel>, mut commands: Commands) {
+fn on_start_level(
+    _trigger: Trigger<SpawnLevel>,
+    mut commands: Commands,
+    query: Query<Entity, Or<(With<ScoreMarker>, With<StopwatchMarker>, With<DeathsMarker>)>>,
+) {
+    // Restarting a level spawns it again without ending it first.
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+
     commands.spawn((
         TextBundle {
             text: Text::from_section(
@@ -144,6 +247,26 @@ fn on_start_level(_trigger: Trigger<SpawnLevel>, mut commands: Commands) {
         StopwatchMarker,
     ));
 
+    commands.spawn((
+        TextBundle {
+            text: Text::from_section(
+                "Deaths: 0".to_string(),
+                TextStyle {
+                    color: SCORE_COLOR,
+                    ..default()
+                },
+            ),
+            style: Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(10.0),
+                top: Val::Px(70.0),
+                ..default()
+            },
+            ..default()
+        },
+        DeathsMarker,
+    ));
+
     commands.trigger(UpdateScore);
 }
 
@@ -0,0 +1,79 @@
+//! The fixed-step simulation.
+//!
+//! Everything that affects how a level plays out runs in `FixedUpdate`, at
+//! [`TICKS_PER_SECOND`]. Physics steps by exactly one tick at a time, and
+//! movement and rotation step by [`TICK_SECS`] rather than reading [`Time`].
+//! Anything random draws from [`SimulationRng`], which is seeded at the
+//! start of each level. Given the same seed and the same input every tick, a
+//! level plays out exactly the same way, which is what replays rely on (see
+//! [`super::replay`]).
+//!
+//! Only the `Update` schedule reads the real frame time, for things that
+//! don't feed back into the simulation like animation and the camera.
+//!
+//! Physics is built with rapier's `enhanced-determinism` feature, but our own
+//! maths uses the platform's `sin` and `cos`, so a replay is only certain to
+//! play back the same way on the platform it was recorded on.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(Time::<Fixed>::from_hz(TICKS_PER_SECOND));
+    app.add_systems(Startup, step_physics_by_ticks);
+    app.init_resource::<SimulationRng>();
+}
+
+/// How many times the simulation steps each second.
+pub const TICKS_PER_SECOND: f64 = 64.0;
+/// How long each simulation step is, in seconds.
+pub const TICK_SECS: f32 = 1.0 / TICKS_PER_SECOND as f32;
+
+/// Physics runs in the fixed schedule, but by default still steps by the
+/// time that passed. Step by exactly one tick instead.
+fn step_physics_by_ticks(mut rapier_config: ResMut<RapierConfiguration>) {
+    rapier_config.timestep_mode = TimestepMode::Fixed {
+        dt: TICK_SECS,
+        substeps: 1,
+    };
+}
+
+/// The random number generator for the simulation.
+#[derive(Resource, Debug, Clone)]
+pub struct SimulationRng {
+    rng: StdRng,
+}
+
+impl Default for SimulationRng {
+    fn default() -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(0),
+        }
+    }
+}
+
+impl SimulationRng {
+    /// Start the sequence of random numbers over from `seed`.
+    pub fn reseed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+}
+
+impl RngCore for SimulationRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
@@ -3,10 +3,15 @@ use bevy_rapier2d::prelude::*;
 
 use crate::{
     game::{
-        animation::{AnimationSequence, AnimationTimer, PlayerAnimation},
-        assets::{HandleMap, ImageKey},
-        movement::{PreviousPhysicalTranslation, SpriteOffset, VisualTranslation},
-        spawn::player::{SpriteMarker, Velocity},
+        ai::DucklingBrain,
+        animation::{AnimationParams, SpriteAnimator},
+        assets::{AnimationKey, HandleMap, ImageKey},
+        gravity::GravityFrame,
+        movement::{
+            JumpState, Movement, MovementController, PreviousPhysicalTranslation, SpriteOffset,
+            VisualTranslation,
+        },
+        spawn::player::{IsOnGround, SpriteMarker, Velocity},
     },
     screen::Screen,
 };
@@ -16,6 +21,8 @@ use super::level::DucklingSpawnPoint;
 pub(super) fn plugin(app: &mut App) {
     app.observe(spawn_duckling);
     app.register_type::<Duckling>();
+    app.register_type::<DucklingKind>();
+    app.register_type::<DucklingHome>();
 }
 
 #[derive(Event, Debug)]
@@ -25,53 +32,158 @@ pub struct SpawnDuckling;
 #[reflect(Component)]
 pub struct Duckling;
 
+/// Where the duckling started out, in level space.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default, Reflect)]
+#[reflect(Component)]
+pub struct DucklingHome(pub Vec2);
+
+/// The different sorts of duckling. Set per duckling in Tiled with a `kind`
+/// property on the spawn point.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub enum DucklingKind {
+    #[default]
+    Normal,
+    /// Smaller and worth a lot more.
+    Golden,
+    /// Sleeps until the world is turned upside-down, and can't be picked up
+    /// until then.
+    Sleepy,
+    /// Hops about, and bounces the player up when collected.
+    Bouncy,
+}
+
+impl DucklingKind {
+    /// Look up a kind by the name used in Tiled, e.g. `"golden"`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "normal" => Some(DucklingKind::Normal),
+            "golden" => Some(DucklingKind::Golden),
+            "sleepy" => Some(DucklingKind::Sleepy),
+            "bouncy" => Some(DucklingKind::Bouncy),
+            _ => None,
+        }
+    }
+
+    pub fn image_key(self) -> ImageKey {
+        match self {
+            DucklingKind::Normal => ImageKey::Duckling,
+            DucklingKind::Golden => ImageKey::DucklingGolden,
+            DucklingKind::Sleepy => ImageKey::DucklingSleepy,
+            DucklingKind::Bouncy => ImageKey::DucklingBouncy,
+        }
+    }
+
+    pub fn collider_radius(self) -> f32 {
+        match self {
+            DucklingKind::Normal => 10.0,
+            DucklingKind::Golden => 8.0,
+            DucklingKind::Sleepy => 11.0,
+            DucklingKind::Bouncy => 9.0,
+        }
+    }
+
+    pub fn jump_speed(self) -> f32 {
+        match self {
+            DucklingKind::Bouncy => 300.0,
+            _ => 0.0,
+        }
+    }
+
+    /// Points added to the score when this duckling is collected.
+    pub fn points(self) -> u32 {
+        match self {
+            DucklingKind::Normal => 100,
+            DucklingKind::Golden => 500,
+            DucklingKind::Sleepy => 200,
+            DucklingKind::Bouncy => 150,
+        }
+    }
+
+    /// Colour of the points that pop up when this duckling is collected.
+    pub fn color(self) -> Color {
+        match self {
+            DucklingKind::Normal => Color::WHITE,
+            DucklingKind::Golden => Color::srgb(1.0, 0.85, 0.2),
+            DucklingKind::Sleepy => Color::srgb(0.6, 0.7, 1.0),
+            DucklingKind::Bouncy => Color::srgb(1.0, 0.5, 0.8),
+        }
+    }
+}
+
+/// The character controller that free ducklings walk around with.
+pub fn duckling_controller() -> KinematicCharacterController {
+    KinematicCharacterController {
+        offset: CharacterLength::Absolute(1.0),
+        max_slope_climb_angle: 30_f32.to_radians(),
+        min_slope_slide_angle: 10_f32.to_radians(),
+        ..default()
+    }
+}
+
 fn spawn_duckling(
     _trigger: Trigger<SpawnDuckling>,
     mut commands: Commands,
     image_handles: Res<HandleMap<ImageKey>>,
+    animation_handles: Res<HandleMap<AnimationKey>>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
-    spawn_points: Query<&Transform, With<DucklingSpawnPoint>>,
+    spawn_points: Query<(&DucklingSpawnPoint, &Transform)>,
 ) {
     let layout = TextureAtlasLayout::from_grid(UVec2::splat(32), 4, 1, Some(UVec2::splat(1)), None);
     let texture_atlas_layout = texture_atlas_layouts.add(layout);
-    let player_animation = PlayerAnimation::new();
 
-    for spawn_point in spawn_points.iter() {
-        let startx = spawn_point.translation.x;
-        let starty = spawn_point.translation.y;
-        log::info!("SPAWN DUCKLING AT: {}, {}", startx, starty);
+    for (spawn_point, transform) in spawn_points.iter() {
+        let kind = spawn_point.kind;
+        let brain = DucklingBrain::new(kind);
+        let startx = transform.translation.x;
+        let starty = transform.translation.y;
+        log::info!("SPAWN {:?} DUCKLING AT: {}, {}", kind, startx, starty);
 
         commands
             .spawn((
                 Name::new("Duckling"),
-                Duckling,
+                (Duckling, kind, DucklingHome(Vec2::new(startx, starty))),
                 SpatialBundle {
                     transform: Transform::from_xyz(startx, starty, 0.),
                     ..default()
                 },
                 Velocity::default(),
                 StateScoped(Screen::Playing),
-                Collider::ball(10.0),
+                Collider::ball(kind.collider_radius()),
                 Friction::coefficient(0.0),
                 Restitution::coefficient(1.0),
-                RigidBody::Dynamic,
-                PreviousPhysicalTranslation(Vec2::new(startx, starty)),
-                VisualTranslation(Vec2::new(startx, starty)),
+                // Ducklings walk around like the player does, see `game::ai`.
+                RigidBody::KinematicPositionBased,
+                duckling_controller(),
+                (
+                    IsOnGround::default(),
+                    JumpState::default(),
+                    GravityFrame::default(),
+                ),
+                MovementController::default(),
+                Movement {
+                    speed: 150.0,
+                    jump_speed: kind.jump_speed(),
+                    ..default()
+                },
+                (brain, AnimationParams::default()),
+                (
+                    PreviousPhysicalTranslation(Vec2::new(startx, starty)),
+                    VisualTranslation(Vec2::new(startx, starty)),
+                ),
             ))
             .with_children(|parent| {
                 parent.spawn((
                     SpriteBundle {
-                        texture: image_handles[&ImageKey::Duckling].clone_weak(),
+                        texture: image_handles[&kind.image_key()].clone_weak(),
                         transform: Transform::from_xyz(0.0, 0.0, 0.0),
                         ..Default::default()
                     },
                     TextureAtlas {
                         layout: texture_atlas_layout.clone(),
-                        index: player_animation.get_atlas_index(),
+                        index: 0,
                     },
-                    AnimationSequence::loop_forwards(0, 3),
-                    AnimationTimer(Timer::from_seconds(0.2, TimerMode::Repeating)),
-                    player_animation.clone(),
+                    SpriteAnimator::new(animation_handles[&AnimationKey::Duckling].clone_weak()),
                     SpriteOffset(Vec2::new(2.0, 1.0)),
                     SpriteMarker,
                 ));
@@ -0,0 +1,65 @@
+//! Spawn the level exits. Exits are drawn as a nest, which stays faded out
+//! until enough ducklings have been collected to use it.
+
+use bevy::prelude::*;
+
+use crate::{
+    game::{
+        assets::{HandleMap, ImageKey},
+        score::Score,
+    },
+    AppSet,
+};
+
+use super::level::{ExitSpawnPoint, LevelFinishPoint};
+
+pub(super) fn plugin(app: &mut App) {
+    app.observe(spawn_exit);
+    app.register_type::<LevelFinishPoint>();
+    app.add_systems(Update, update_exit_sprites.in_set(AppSet::Update));
+}
+
+#[derive(Event, Debug)]
+pub struct SpawnExit;
+
+/// How opaque an exit is while it still needs more ducklings.
+const INACTIVE_ALPHA: f32 = 0.35;
+
+fn spawn_exit(
+    _trigger: Trigger<SpawnExit>,
+    mut commands: Commands,
+    image_handles: Res<HandleMap<ImageKey>>,
+    spawn_points: Query<(&ExitSpawnPoint, &Transform, &Parent)>,
+) {
+    for (spawn_point, transform, level) in spawn_points.iter() {
+        // Exits are part of the level so that they rotate with it.
+        commands.entity(level.get()).with_children(|parent| {
+            parent.spawn((
+                Name::new("Exit"),
+                LevelFinishPoint {
+                    ducklings_required: spawn_point.ducklings_required,
+                },
+                SpriteBundle {
+                    texture: image_handles[&ImageKey::Nest].clone_weak(),
+                    transform: *transform,
+                    sprite: Sprite {
+                        color: Color::WHITE.with_alpha(INACTIVE_ALPHA),
+                        ..default()
+                    },
+                    ..default()
+                },
+            ));
+        });
+    }
+}
+
+fn update_exit_sprites(score: Res<Score>, mut query: Query<(&LevelFinishPoint, &mut Sprite)>) {
+    for (exit, mut sprite) in &mut query {
+        let alpha = if exit.is_active(score.ducklings_collected) {
+            1.0
+        } else {
+            INACTIVE_ALPHA
+        };
+        sprite.color.set_alpha(alpha);
+    }
+}
@@ -0,0 +1,106 @@
+//! Spawn foxes. Foxes walk around the level like ducklings do, see
+//! [`crate::game::fox`] for what they get up to.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    game::{
+        animation::{AnimationParams, SpriteAnimator},
+        assets::{AnimationKey, HandleMap, ImageKey},
+        fox::FoxBrain,
+        gravity::GravityFrame,
+        movement::{
+            JumpState, Movement, MovementController, PreviousPhysicalTranslation, SpriteOffset,
+            VisualTranslation,
+        },
+        spawn::player::{IsOnGround, SpriteMarker, Velocity},
+    },
+    screen::Screen,
+};
+
+use super::level::FoxSpawnPoint;
+
+pub(super) fn plugin(app: &mut App) {
+    app.observe(spawn_fox);
+    app.register_type::<Fox>();
+}
+
+#[derive(Event, Debug)]
+pub struct SpawnFox;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Fox;
+
+fn spawn_fox(
+    _trigger: Trigger<SpawnFox>,
+    mut commands: Commands,
+    image_handles: Res<HandleMap<ImageKey>>,
+    animation_handles: Res<HandleMap<AnimationKey>>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    spawn_points: Query<&Transform, With<FoxSpawnPoint>>,
+) {
+    let layout = TextureAtlasLayout::from_grid(UVec2::splat(32), 4, 3, Some(UVec2::splat(1)), None);
+    let texture_atlas_layout = texture_atlas_layouts.add(layout);
+
+    for transform in spawn_points.iter() {
+        let brain = FoxBrain::default();
+        let startx = transform.translation.x;
+        let starty = transform.translation.y;
+
+        commands
+            .spawn((
+                Name::new("Fox"),
+                Fox,
+                SpatialBundle {
+                    transform: Transform::from_xyz(startx, starty, 0.),
+                    ..default()
+                },
+                Velocity::default(),
+                StateScoped(Screen::Playing),
+                Collider::ball(12.0),
+                Friction::coefficient(0.0),
+                Restitution::coefficient(1.0),
+                RigidBody::KinematicPositionBased,
+                KinematicCharacterController {
+                    offset: CharacterLength::Absolute(1.0),
+                    max_slope_climb_angle: 30_f32.to_radians(),
+                    min_slope_slide_angle: 10_f32.to_radians(),
+                    ..default()
+                },
+                (
+                    IsOnGround::default(),
+                    JumpState::default(),
+                    GravityFrame::default(),
+                ),
+                MovementController::default(),
+                Movement {
+                    speed: 160.0,
+                    jump_speed: 320.0,
+                    ..default()
+                },
+                (brain, AnimationParams::default()),
+                (
+                    PreviousPhysicalTranslation(Vec2::new(startx, starty)),
+                    VisualTranslation(Vec2::new(startx, starty)),
+                ),
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    SpriteBundle {
+                        texture: image_handles[&ImageKey::Fox].clone_weak(),
+                        transform: Transform::from_xyz(0.0, 0.0, 0.0),
+                        ..Default::default()
+                    },
+                    TextureAtlas {
+                        layout: texture_atlas_layout.clone(),
+                        index: 0,
+                    },
+                    SpriteAnimator::new(animation_handles[&AnimationKey::Fox].clone_weak()),
+                    SpriteOffset(Vec2::new(0.0, 4.0)),
+                    SpriteMarker,
+                ));
+            });
+    }
+}
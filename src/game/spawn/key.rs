@@ -0,0 +1,53 @@
+//! Spawn keys. Keys are picked up by walking into them, and open the doors
+//! of the same color.
+
+use bevy::prelude::*;
+
+use crate::game::{
+    assets::{HandleMap, ImageKey},
+    puzzle::KeyColor,
+};
+
+use super::level::KeySpawnPoint;
+
+pub(super) fn plugin(app: &mut App) {
+    app.observe(spawn_key);
+    app.register_type::<Key>();
+}
+
+#[derive(Event, Debug)]
+pub struct SpawnKey;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Key {
+    pub color: KeyColor,
+}
+
+fn spawn_key(
+    _trigger: Trigger<SpawnKey>,
+    mut commands: Commands,
+    image_handles: Res<HandleMap<ImageKey>>,
+    spawn_points: Query<(&KeySpawnPoint, &Transform, &Parent)>,
+) {
+    for (spawn_point, transform, level) in spawn_points.iter() {
+        // Keys are part of the level so that they rotate with it.
+        commands.entity(level.get()).with_children(|parent| {
+            parent.spawn((
+                Name::new("Key"),
+                Key {
+                    color: spawn_point.color,
+                },
+                SpriteBundle {
+                    texture: image_handles[&ImageKey::Key].clone_weak(),
+                    transform: *transform,
+                    sprite: Sprite {
+                        color: spawn_point.color.color(),
+                        ..default()
+                    },
+                    ..default()
+                },
+            ));
+        });
+    }
+}
@@ -2,6 +2,7 @@
 
 use bevy::prelude::*;
 use bevy_ecs_tiled::prelude::*;
+use serde::Deserialize;
 
 #[cfg(feature = "dev")]
 use bevy::dev_tools::states::log_transitions;
@@ -9,6 +10,10 @@ use bevy::dev_tools::states::log_transitions;
 use crate::{
     game::{
         frames::ResetFrameCounter,
+        gravity::GravityMode,
+        hazard::HazardMode,
+        puzzle::{KeyColor, SwitchAction},
+        rotation::{PlayerRotation, RotationController, RotationMode, RotationProfile},
         score::{OverallScore, Score, UpdateScore},
     },
     screen::Screen,
@@ -16,16 +21,24 @@ use crate::{
 };
 
 use super::{
-    duckling::{Duckling, SpawnDuckling},
+    duckling::{Duckling, DucklingKind, SpawnDuckling},
+    exit::SpawnExit,
+    fox::{Fox, SpawnFox},
+    key::SpawnKey,
+    platform::SpawnPlatform,
     player::{Player, SpawnPlayer},
+    spikes::SpawnSpikes,
+    switch::SpawnSwitch,
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.init_state::<LevelState>();
+    app.init_resource::<CurrentLevel>();
     app.observe(start_new_game);
     app.observe(spawn_level);
     app.observe(cleanup_level);
     app.observe(on_end_level);
+    app.observe(on_restart_level);
     app.observe(on_game_completed);
     app.add_systems(Update, on_level_added.run_if(in_state(Screen::Playing)));
     app.add_systems(Update, on_fade_completed.run_if(in_state(Screen::Playing)));
@@ -35,13 +48,15 @@ pub(super) fn plugin(app: &mut App) {
     app.add_systems(Update, log_transitions::<LevelState>);
 }
 
+/// Where the current level is between fading in, being played and fading out.
 #[derive(States, Debug, Hash, PartialEq, Eq, Clone, Default)]
-enum LevelState {
+pub enum LevelState {
     #[default]
     Inactive,
     EndLevelFadeOut,
     StartLevelFadeIn,
     Active,
+    RestartFadeOut,
     CompletedFadeOut,
 }
 
@@ -73,6 +88,12 @@ fn on_fade_completed(
                     }
                 }
             }
+            LevelState::RestartFadeOut => {
+                commands.trigger(CleanupLevel);
+                commands.trigger(SpawnLevel);
+                commands.trigger(FadeIn { duration: 0.25 });
+                next_state.set(LevelState::StartLevelFadeIn);
+            }
             LevelState::StartLevelFadeIn => {
                 next_state.set(LevelState::Active);
             }
@@ -85,22 +106,36 @@ fn on_fade_completed(
     }
 }
 
-#[derive(Resource, Clone, Copy, Debug)]
+#[derive(Resource, Clone, Copy, Debug, Default)]
 pub struct CurrentLevel(i32);
 
+impl CurrentLevel {
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+
+    /// Keep the level inside a campaign of `level_count` levels.
+    pub fn clamp(&mut self, level_count: usize) {
+        self.0 = self.0.min(level_count.saturating_sub(1) as i32);
+    }
+}
+
+/// Start a new game from the level at the given index.
 #[derive(Event, Debug)]
-pub struct StartNewGame;
+pub struct StartNewGame(pub usize);
 
 fn start_new_game(
-    _trigger: Trigger<StartNewGame>,
+    trigger: Trigger<StartNewGame>,
     mut commands: Commands,
     mut next_state: ResMut<NextState<LevelState>>,
 ) {
     next_state.set(LevelState::StartLevelFadeIn);
-    commands.insert_resource(OverallScore::default());
+    commands.insert_resource(OverallScore {
+        full_run: trigger.event().0 == 0,
+        ..default()
+    });
     commands.insert_resource(Score::default());
-    commands.insert_resource(Levels::default());
-    commands.insert_resource(CurrentLevel(0));
+    commands.insert_resource(CurrentLevel(trigger.event().0 as i32));
     commands.trigger(FadeOut { duration: 0.5 });
     commands.trigger(SpawnLevel);
 }
@@ -120,87 +155,195 @@ fn spawn_level(
     asset_server: ResMut<AssetServer>,
     current_level: Res<CurrentLevel>,
     levels: Res<Levels>,
+    mut maps: ResMut<Assets<TiledMap>>,
     mut score: ResMut<Score>,
+    mut next_state: ResMut<NextState<LevelState>>,
+    mut next_screen: ResMut<NextState<Screen>>,
 ) {
-    let level = levels.current(*current_level).unwrap();
-    let mapx = level.size.x as f32 * 16. - 16.;
-    let mapy = level.size.y as f32 * 16. - 16.;
+    // The campaign can be hot reloaded with fewer levels while playing.
+    let Some(level) = levels.current(*current_level) else {
+        log::warn!(
+            "Level {} is not in the campaign, going back to level select",
+            current_level.index()
+        );
+        next_state.set(LevelState::Inactive);
+        next_screen.set(Screen::LevelSelect);
+        return;
+    };
     let map_handle: Handle<TiledMap> = asset_server.load(&level.map);
+    // Restarting a level reuses its map, which is still loaded, but
+    // `bevy_ecs_tiled` only spawns tiles for a map when it is added or
+    // modified. Borrowing it mutably marks it as modified.
+    let _ = maps.get_mut(&map_handle);
 
-    // Set up score details.
+    // Set up score details. The duckling total is filled in once the map has loaded.
     score.score = 0;
-    score.ducklings_total = level.duckling_spawn_points.len() as u32;
     score.ducklings_collected = 0;
+    score.deaths = 0;
     score.stopwatch.reset();
 
     commands
         .spawn((
             Name::new("Level"),
             LevelMarker,
+            RotationController::default(),
+            PlayerRotation::default(),
             SpatialBundle {
                 transform: Transform::from_xyz(0., 0., 0.0),
                 ..default()
             },
         ))
         .with_children(|parent| {
+            // The map is positioned once we know its size.
             parent.spawn(TiledMapBundle {
                 tiled_map: map_handle,
                 tiled_settings: TiledMapSettings {
                     collision_layer_names: ObjectNames::None,
                     collision_object_names: ObjectNames::All,
                 },
-                transform: Transform::from_xyz(-mapx, -mapy, 0.0),
                 ..Default::default()
             });
+        });
+
+    log::warn!("SPAWNED LEVEL");
 
+    // commands.trigger(ResetFrameCounter);
+    commands.trigger(UpdateScore);
+}
+
+/// Finish setting up the level once its map has loaded and its tiles have
+/// been spawned. This checks the load state rather than waiting for an asset
+/// event, because restarting a level reuses a map that is already loaded.
+///
+/// Waiting for the tiles means they are in place before the level clock
+/// starts, see [`crate::game::tiles`].
+fn on_level_added(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    maps: Res<Assets<TiledMap>>,
+    mut score: ResMut<Score>,
+    mut map_query: Query<
+        (Entity, &Handle<TiledMap>, &Parent, &mut Transform),
+        (With<TiledMapMarker>, Without<LevelLoaded>),
+    >,
+) {
+    for (entity, handle, level, mut transform) in map_query.iter_mut() {
+        if !asset_server.is_loaded_with_dependencies(handle) {
+            continue;
+        }
+        let Some(tiled_map) = maps.get(handle) else {
+            continue;
+        };
+
+        log::warn!("MAP LOADED");
+        let layout = LevelLayout::from_map(&tiled_map.map);
+        transform.translation = layout.map_offset().extend(0.0);
+
+        commands.entity(level.get()).with_children(|parent| {
             // Set player spawn point.
             parent.spawn((
                 PlayerSpawnPoint,
                 SpatialBundle {
-                    transform: Transform::from_xyz(
-                        level.start_tile.x as f32 * 32.,
-                        level.start_tile.y as f32 * 32.,
-                        0.,
-                    ),
+                    transform: Transform::from_translation(layout.player_start.extend(0.)),
                     ..default()
                 },
             ));
 
-            for p in &level.duckling_spawn_points {
+            for duckling in &layout.ducklings {
                 parent.spawn((
-                    DucklingSpawnPoint,
+                    DucklingSpawnPoint {
+                        kind: duckling.kind,
+                    },
                     SpatialBundle {
-                        transform: Transform::from_xyz(p.x as f32 * 32., p.y as f32 * 32., 0.),
+                        transform: Transform::from_translation(duckling.position.extend(0.)),
                         ..default()
                     },
                 ));
             }
-        });
 
-    log::warn!("SPAWNED LEVEL");
+            // Exits need all of the ducklings unless the map says otherwise.
+            let ducklings_total = layout.ducklings.len() as u32;
+            for exit in &layout.exits {
+                parent.spawn((
+                    ExitSpawnPoint {
+                        ducklings_required: exit
+                            .ducklings_required
+                            .map_or(ducklings_total, |required| required.min(ducklings_total)),
+                    },
+                    SpatialBundle {
+                        transform: Transform::from_translation(exit.position.extend(0.)),
+                        ..default()
+                    },
+                ));
+            }
 
-    // commands.trigger(ResetFrameCounter);
-    commands.trigger(UpdateScore);
-}
+            for p in &layout.spikes {
+                parent.spawn((
+                    SpikesSpawnPoint,
+                    SpatialBundle {
+                        transform: Transform::from_translation(p.extend(0.)),
+                        ..default()
+                    },
+                ));
+            }
 
-fn on_level_added(
-    mut events: EventReader<AssetEvent<TiledMap>>,
-    mut commands: Commands,
-    map: Query<(Entity, &Handle<TiledMap>), Without<LevelLoaded>>,
-) {
-    for event in events.read() {
-        if let AssetEvent::LoadedWithDependencies { id } = event {
-            for (entity, handle) in map.iter() {
-                if handle.id() == *id {
-                    log::warn!("MAP LOADED");
-                    commands.entity(entity).insert(LevelLoaded);
-                    commands.trigger(SpawnPlayer);
-                    commands.trigger(SpawnDuckling);
-                    commands.trigger(ResetFrameCounter);
-                    return;
-                }
+            for platform in &layout.platforms {
+                parent.spawn((
+                    PlatformSpawnPoint(platform.clone()),
+                    SpatialBundle {
+                        transform: Transform::from_translation(platform.path[0].extend(0.)),
+                        ..default()
+                    },
+                ));
             }
-        }
+
+            for p in &layout.foxes {
+                parent.spawn((
+                    FoxSpawnPoint,
+                    SpatialBundle {
+                        transform: Transform::from_translation(p.extend(0.)),
+                        ..default()
+                    },
+                ));
+            }
+
+            for key in &layout.keys {
+                parent.spawn((
+                    KeySpawnPoint { color: key.color },
+                    SpatialBundle {
+                        transform: Transform::from_translation(key.position.extend(0.)),
+                        ..default()
+                    },
+                ));
+            }
+
+            for switch in &layout.switches {
+                parent.spawn((
+                    SwitchSpawnPoint {
+                        action: switch.action.clone(),
+                    },
+                    SpatialBundle {
+                        transform: Transform::from_translation(switch.position.extend(0.)),
+                        ..default()
+                    },
+                ));
+            }
+        });
+
+        score.ducklings_total = layout.ducklings.len() as u32;
+        commands.insert_resource(layout);
+        commands.entity(entity).insert(LevelLoaded);
+        commands.trigger(SpawnPlayer);
+        commands.trigger(SpawnDuckling);
+        commands.trigger(SpawnFox);
+        commands.trigger(SpawnExit);
+        commands.trigger(SpawnSpikes);
+        commands.trigger(SpawnPlatform);
+        commands.trigger(SpawnKey);
+        commands.trigger(SpawnSwitch);
+        commands.trigger(ResetFrameCounter);
+        commands.trigger(UpdateScore);
+        return;
     }
 }
 
@@ -208,14 +351,54 @@ fn on_level_added(
 pub struct PlayerSpawnPoint;
 
 #[derive(Component, Debug, Clone, Copy, PartialEq, Default, Reflect)]
-pub struct DucklingSpawnPoint;
+pub struct DucklingSpawnPoint {
+    pub kind: DucklingKind,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default, Reflect)]
+pub struct FoxSpawnPoint;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default, Reflect)]
+pub struct ExitSpawnPoint {
+    pub ducklings_required: u32,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default, Reflect)]
+pub struct SpikesSpawnPoint;
 
+#[derive(Component, Debug, Clone, PartialEq, Default, Reflect)]
+pub struct PlatformSpawnPoint(pub PlatformLayout);
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default, Reflect)]
+pub struct KeySpawnPoint {
+    pub color: KeyColor,
+}
+
+#[derive(Component, Debug, Clone, PartialEq, Reflect)]
+pub struct SwitchSpawnPoint {
+    pub action: SwitchAction,
+}
+
+/// An exit that ends the level when the player reaches it, once enough
+/// ducklings have been collected.
 #[derive(Component, Debug, Clone, Copy, PartialEq, Default, Reflect)]
-pub struct LevelFinishPoint;
+pub struct LevelFinishPoint {
+    pub ducklings_required: u32,
+}
+
+impl LevelFinishPoint {
+    pub fn is_active(&self, ducklings_collected: u32) -> bool {
+        ducklings_collected >= self.ducklings_required
+    }
+}
 
 #[derive(Event, Debug)]
 pub struct EndLevel;
 
+/// Triggered when the player finishes a level, just before [`EndLevel`].
+#[derive(Event, Debug)]
+pub struct LevelCompleted;
+
 #[derive(Event, Debug)]
 pub struct CleanupLevel;
 
@@ -226,7 +409,7 @@ fn exit_playing(mut commands: Commands) {
 fn cleanup_level(
     _trigger: Trigger<CleanupLevel>,
     mut commands: Commands,
-    query: Query<Entity, Or<(With<LevelMarker>, With<Player>, With<Duckling>)>>,
+    query: Query<Entity, Or<(With<LevelMarker>, With<Player>, With<Duckling>, With<Fox>)>>,
 ) {
     for entity in query.iter() {
         commands.entity(entity).despawn_recursive();
@@ -242,206 +425,358 @@ fn on_end_level(
     next_state.set(LevelState::EndLevelFadeOut);
 }
 
-#[derive(Debug, Clone, PartialEq, Reflect)]
+/// Start the current level again from the beginning.
+///
+/// The level is cleaned up and spawned again, which resets the level's
+/// [`Score`] and rewinds the stopwatch to the [`OverallScore`] carried into
+/// the level. Restarts are ignored while the level is fading in or out.
+#[derive(Event, Debug)]
+pub struct RestartLevel;
+
+fn on_restart_level(
+    _trigger: Trigger<RestartLevel>,
+    mut commands: Commands,
+    state: Res<State<LevelState>>,
+    mut next_state: ResMut<NextState<LevelState>>,
+) {
+    if *state.get() != LevelState::Active {
+        return;
+    }
+
+    commands.trigger(FadeOut { duration: 0.25 });
+    next_state.set(LevelState::RestartFadeOut);
+}
+
+/// A single entry in the campaign manifest.
+#[derive(Debug, Clone, PartialEq, Reflect, Deserialize)]
 pub struct Level {
+    /// Name shown to the player.
+    pub name: String,
+    /// Path to the Tiled map, relative to the assets folder.
     pub map: String,
-    pub size: IVec2,
-    pub start_tile: IVec2,
-    pub duckling_spawn_points: Vec<IVec2>,
+    /// How the world rotates during the level.
+    #[serde(default)]
+    pub rotation: RotationProfile,
+    /// Whether the world rotates by itself, is turned by the player, or both.
+    #[serde(default)]
+    pub rotation_mode: RotationMode,
+    /// Target completion time in seconds.
+    pub par_time: f32,
+    /// What happens when the player touches a hazard.
+    #[serde(default)]
+    pub hazard_mode: HazardMode,
+    /// Which way gravity pulls during the level.
+    #[serde(default)]
+    pub gravity_mode: GravityMode,
+    /// Whether the player can slide down walls and jump off them.
+    #[serde(default)]
+    pub wall_jumps: bool,
 }
 
-#[derive(Debug, Clone, PartialEq, Reflect, Resource)]
+/// The levels in the campaign. This is built from the campaign manifest,
+/// see [`crate::game::campaign`].
+#[derive(Debug, Clone, PartialEq, Reflect, Resource, Default)]
 pub struct Levels {
     levels: Vec<Level>,
 }
 
-impl Default for Levels {
-    fn default() -> Self {
-        Self {
-            levels: vec![
-                Level {
-                    map: "level1.tmx".to_string(),
-                    size: IVec2::new(21, 21),
-                    start_tile: IVec2::new(-2, 2),
-                    duckling_spawn_points: vec![
-                        IVec2::new(0, -6),
-                        IVec2::new(0, 8),
-                        IVec2::new(6, 3),
-                    ],
-                },
-                Level {
-                    map: "level2.tmx".to_string(),
-                    size: IVec2::new(21, 21),
-                    start_tile: IVec2::new(-8, -8),
-                    duckling_spawn_points: vec![
-                        IVec2::new(-8, 8),
-                        IVec2::new(8, -5),
-                        IVec2::new(4, 0),
-                        IVec2::new(-6, 1),
-                    ],
-                },
-                Level {
-                    map: "level3.tmx".to_string(),
-                    size: IVec2::new(21, 21),
-                    start_tile: IVec2::new(-2, -3),
-                    duckling_spawn_points: vec![
-                        IVec2::new(-8, 0),
-                        IVec2::new(8, 0),
-                        IVec2::new(8, 8),
-                        IVec2::new(-8, -8),
-                        IVec2::new(8, -8),
-                        IVec2::new(-8, 8),
-                    ],
-                },
-                Level {
-                    map: "level4.tmx".to_string(),
-                    size: IVec2::new(21, 21),
-                    start_tile: IVec2::new(5, 5),
-                    duckling_spawn_points: vec![
-                        IVec2::new(-7, -7),
-                        IVec2::new(7, -7),
-                        IVec2::new(-7, 0),
-                        IVec2::new(7, 0),
-                        IVec2::new(0, 7),
-                        IVec2::new(-3, 8),
-                        IVec2::new(3, 8),
-                    ],
-                },
-                Level {
-                    map: "level5.tmx".to_string(),
-                    size: IVec2::new(21, 21),
-                    start_tile: IVec2::new(0, 0),
-                    duckling_spawn_points: vec![
-                        IVec2::new(-5, -2),
-                        IVec2::new(5, -2),
-                        IVec2::new(-5, 1),
-                        IVec2::new(5, 1),
-                        IVec2::new(-9, 4),
-                        IVec2::new(9, -4),
-                        IVec2::new(1, 8),
-                        IVec2::new(-2, -4),
-                        IVec2::new(2, -4),
-                        IVec2::new(3, 6),
-                    ],
-                },
-                Level {
-                    map: "level6.tmx".to_string(),
-                    size: IVec2::new(31, 31),
-                    start_tile: IVec2::new(-2, 4),
-                    duckling_spawn_points: vec![
-                        IVec2::new(-13, -1),
-                        IVec2::new(-13, -3),
-                        IVec2::new(0, -13),
-                        IVec2::new(5, -9),
-                        IVec2::new(-7, -13),
-                        IVec2::new(9, -4),
-                        IVec2::new(-11, 11),
-                        IVec2::new(0, 11),
-                        IVec2::new(13, 1),
-                        IVec2::new(3, 7),
-                    ],
-                },
-                Level {
-                    map: "level7.tmx".to_string(),
-                    size: IVec2::new(31, 31),
-                    start_tile: IVec2::new(0, 2),
-                    duckling_spawn_points: vec![
-                        IVec2::new(-3, 0),
-                        IVec2::new(3, 0),
-                        IVec2::new(0, -3),
-                        IVec2::new(-11, 0),
-                        IVec2::new(11, 0),
-                        IVec2::new(0, 11),
-                        IVec2::new(0, -11),
-                        IVec2::new(9, 9),
-                        IVec2::new(9, -9),
-                        IVec2::new(-9, -9),
-                        IVec2::new(-9, 9),
-                    ],
-                },
-                Level {
-                    map: "level8.tmx".to_string(),
-                    size: IVec2::new(31, 31),
-                    start_tile: IVec2::new(-2, -2),
-                    duckling_spawn_points: vec![
-                        IVec2::new(-4, 2),
-                        IVec2::new(-8, 0),
-                        IVec2::new(4, 0),
-                        IVec2::new(6, -3),
-                        IVec2::new(4, -8),
-                        IVec2::new(8, 0),
-                        IVec2::new(10, 7),
-                        IVec2::new(-8, 6),
-                        IVec2::new(0, 8),
-                        IVec2::new(13, 7),
-                        IVec2::new(12, -6),
-                        IVec2::new(-10, -6),
-                        IVec2::new(-11, -10),
-                    ],
-                },
-                Level {
-                    map: "level9.tmx".to_string(),
-                    size: IVec2::new(25, 25),
-                    start_tile: IVec2::new(10, -10),
-                    duckling_spawn_points: vec![
-                        IVec2::new(3, 3),
-                        IVec2::new(-3, 3),
-                        IVec2::new(-3, -3),
-                        IVec2::new(3, -3),
-                        IVec2::new(-10, 0),
-                        IVec2::new(10, 0),
-                        IVec2::new(3, 10),
-                        IVec2::new(-3, -10),
-                        IVec2::new(10, 10),
-                        IVec2::new(-10, -10),
-                        IVec2::new(-10, 10),
-                        IVec2::new(4, 11),
-                        IVec2::new(4, -11),
-                    ],
-                },
-                Level {
-                    map: "level11.tmx".to_string(),
-                    size: IVec2::new(25, 25),
-                    start_tile: IVec2::new(7, 1),
-                    duckling_spawn_points: vec![
-                        IVec2::new(0, 1),
-                        IVec2::new(-1, 1),
-                        IVec2::new(-2, 1),
-                        IVec2::new(-3, 1),
-                        IVec2::new(1, 1),
-                        IVec2::new(2, 1),
-                        IVec2::new(3, 1),
-                        IVec2::new(4, 7),
-                        IVec2::new(5, 7),
-                        IVec2::new(6, 7),
-                        IVec2::new(7, 7),
-                        IVec2::new(8, 7),
-                        IVec2::new(-4, 7),
-                        IVec2::new(-5, 7),
-                        IVec2::new(-6, 7),
-                        IVec2::new(-7, 7),
-                        IVec2::new(-8, 7),
-                        IVec2::new(-4, -5),
-                        IVec2::new(-5, -5),
-                        IVec2::new(-6, -5),
-                        IVec2::new(-7, -5),
-                        IVec2::new(-8, -5),
-                        IVec2::new(4, -5),
-                        IVec2::new(5, -5),
-                        IVec2::new(6, -5),
-                        IVec2::new(7, -5),
-                        IVec2::new(8, -5),
-                        IVec2::new(-11, -11),
-                        IVec2::new(-11, 11),
-                        IVec2::new(11, -11),
-                    ],
-                },
-            ],
+/// Name of the Tiled object layer that holds the player start, the duckling
+/// and fox spawn points, the exits, the spikes, the platforms, the keys and
+/// the switches.
+const OBJECT_LAYER_NAME: &str = "Objects";
+
+/// Platform width in tiles, if the `Platform` object doesn't give one.
+const DEFAULT_PLATFORM_WIDTH: u32 = 2;
+/// Platform speed in pixels per second, if the `Platform` object doesn't give one.
+const DEFAULT_PLATFORM_SPEED: f32 = 64.0;
+
+/// Optional integer property on an `Exit` object giving how many ducklings
+/// must be collected before the exit opens.
+const DUCKLINGS_REQUIRED_PROPERTY: &str = "ducklings_required";
+
+/// Optional string property on a `Duckling` object naming its
+/// [`DucklingKind`]. Ducklings without it are normal ones.
+const DUCKLING_KIND_PROPERTY: &str = "kind";
+
+/// String property on a `Key` object naming its [`KeyColor`].
+const KEY_COLOR_PROPERTY: &str = "color";
+
+/// Optional bool property on a tile layer that switches turn on and off,
+/// which hides the layer until a switch is pressed.
+const START_HIDDEN_PROPERTY: &str = "start_hidden";
+
+/// Level details read from the Tiled map once it has loaded.
+///
+/// All positions are in level space, where the origin is the centre of the
+/// map and Y points up.
+#[derive(Resource, Debug, Clone, PartialEq, Default, Reflect)]
+pub struct LevelLayout {
+    /// Map size in tiles.
+    pub size: UVec2,
+    /// Tile size in pixels.
+    pub tile_size: Vec2,
+    pub player_start: Vec2,
+    pub ducklings: Vec<DucklingLayout>,
+    pub foxes: Vec<Vec2>,
+    pub exits: Vec<ExitLayout>,
+    pub spikes: Vec<Vec2>,
+    pub platforms: Vec<PlatformLayout>,
+    pub keys: Vec<KeyLayout>,
+    pub switches: Vec<SwitchLayout>,
+    /// Tile layers that switches turn on and off.
+    pub switched_layers: Vec<SwitchedLayerLayout>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Reflect)]
+pub struct DucklingLayout {
+    pub position: Vec2,
+    pub kind: DucklingKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Reflect)]
+pub struct ExitLayout {
+    pub position: Vec2,
+    /// `None` if every duckling is required.
+    pub ducklings_required: Option<u32>,
+}
+
+/// A moving platform, set up with a `Platform` object in Tiled. A polyline
+/// gives the path it moves back and forth along, and a polygon a path it
+/// goes round and round. The object's properties are:
+///
+/// - `width` (int): width in tiles.
+/// - `speed` (float): speed along the path in pixels per second.
+/// - `rotation_speed` (float): how fast it spins in degrees per second,
+///   counter-clockwise.
+#[derive(Debug, Clone, PartialEq, Default, Reflect)]
+pub struct PlatformLayout {
+    /// The path in level space. The platform starts at the first point.
+    pub path: Vec<Vec2>,
+    /// Whether the path joins back up with its start.
+    pub closed: bool,
+    pub width: u32,
+    pub speed: f32,
+    pub rotation_speed: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Reflect)]
+pub struct KeyLayout {
+    pub position: Vec2,
+    pub color: KeyColor,
+}
+
+/// A switch, set up with a `Switch` object in Tiled. Its `action` property is
+/// either `toggle_layer`, which turns the tile layer named by its `layer`
+/// property on and off, or `reverse_rotation`.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub struct SwitchLayout {
+    pub position: Vec2,
+    pub action: SwitchAction,
+}
+
+/// A tile layer that switches turn on and off.
+#[derive(Debug, Clone, PartialEq, Default, Reflect)]
+pub struct SwitchedLayerLayout {
+    pub name: String,
+    /// Cells with a tile, as tile positions counting up from the bottom of the map.
+    pub cells: Vec<UVec2>,
+    pub start_hidden: bool,
+}
+
+impl LevelLayout {
+    pub fn from_map(map: &tiled::Map) -> Self {
+        let mut layout = Self {
+            size: UVec2::new(map.width, map.height),
+            tile_size: Vec2::new(map.tile_width as f32, map.tile_height as f32),
+            ..default()
+        };
+
+        let objects = map
+            .layers()
+            .filter(|layer| layer.name == OBJECT_LAYER_NAME)
+            .filter_map(|layer| layer.as_object_layer());
+        for layer in objects {
+            for object in layer.objects() {
+                let pos = layout.to_level_space(object.x, object.y);
+                match object.name.as_str() {
+                    "PlayerStart" => layout.player_start = pos,
+                    "Spikes" => layout.spikes.push(pos),
+                    "Fox" => layout.foxes.push(pos),
+                    "Duckling" => layout.ducklings.push(DucklingLayout {
+                        position: pos,
+                        kind: string_property(&object.properties, DUCKLING_KIND_PROPERTY)
+                            .map_or_else(DucklingKind::default, |name| {
+                                DucklingKind::from_name(name).unwrap_or_else(|| {
+                                    log::warn!("Unknown duckling kind: {name}");
+                                    DucklingKind::default()
+                                })
+                            }),
+                    }),
+                    "Exit" => layout.exits.push(ExitLayout {
+                        position: pos,
+                        ducklings_required: u32_property(
+                            &object.properties,
+                            DUCKLINGS_REQUIRED_PROPERTY,
+                        ),
+                    }),
+                    "Platform" => {
+                        let platform = layout.platform_layout(&object);
+                        layout.platforms.push(platform);
+                    }
+                    "Key" => {
+                        layout.keys.push(KeyLayout {
+                            position: pos,
+                            color: string_property(&object.properties, KEY_COLOR_PROPERTY)
+                                .map_or_else(KeyColor::default, |name| {
+                                    KeyColor::from_name(name).unwrap_or_else(|| {
+                                        log::warn!("Unknown key color: {name}");
+                                        KeyColor::default()
+                                    })
+                                }),
+                        })
+                    }
+                    "Switch" => match SwitchAction::from_properties(&object.properties) {
+                        Some(action) => layout.switches.push(SwitchLayout {
+                            position: pos,
+                            action,
+                        }),
+                        None => log::warn!("Switch at {pos} has no valid action"),
+                    },
+                    name => log::warn!("Unknown object in {OBJECT_LAYER_NAME} layer: {name}"),
+                }
+            }
+        }
+
+        for layer in map.layers() {
+            let Some(tiles) = layer.as_tile_layer() else {
+                continue;
+            };
+            let start_hidden =
+                bool_property(&layer.properties, START_HIDDEN_PROPERTY).unwrap_or(false);
+            let switched = layout.switches.iter().any(|switch| {
+                matches!(&switch.action, SwitchAction::ToggleLayer(name) if *name == layer.name)
+            });
+            if !start_hidden && !switched {
+                continue;
+            }
+
+            let mut cells = Vec::new();
+            for y in 0..map.height {
+                for x in 0..map.width {
+                    if tiles.get_tile(x as i32, y as i32).is_some() {
+                        cells.push(UVec2::new(x, map.height - 1 - y));
+                    }
+                }
+            }
+            layout.switched_layers.push(SwitchedLayerLayout {
+                name: layer.name.clone(),
+                cells,
+                start_hidden,
+            });
+        }
+
+        layout
+    }
+
+    fn platform_layout(&self, object: &tiled::Object) -> PlatformLayout {
+        let (points, closed) = match &object.shape {
+            tiled::ObjectShape::Polyline { points } => (points.as_slice(), false),
+            tiled::ObjectShape::Polygon { points } => (points.as_slice(), true),
+            _ => (&[(0.0, 0.0)][..], false),
+        };
+        let properties = &object.properties;
+
+        PlatformLayout {
+            path: points
+                .iter()
+                .map(|(x, y)| self.to_level_space(object.x + x, object.y + y))
+                .collect(),
+            closed,
+            width: u32_property(properties, "width").unwrap_or(DEFAULT_PLATFORM_WIDTH),
+            speed: f32_property(properties, "speed").unwrap_or(DEFAULT_PLATFORM_SPEED),
+            rotation_speed: f32_property(properties, "rotation_speed").unwrap_or(0.0),
+        }
+    }
+
+    /// Size of the whole map in pixels.
+    pub fn pixel_size(&self) -> Vec2 {
+        self.size.as_vec2() * self.tile_size
+    }
+
+    /// The area covered by the map, in level space.
+    pub fn bounds(&self) -> Rect {
+        Rect::from_center_size(Vec2::ZERO, self.pixel_size())
+    }
+
+    /// Offset to apply to the tilemap so that the map is centred on the level origin.
+    /// Tiles are anchored at their centre, hence the extra half tile.
+    pub fn map_offset(&self) -> Vec2 {
+        -(self.pixel_size() - self.tile_size) / 2.
+    }
+
+    /// Convert a position in Tiled pixel coordinates (origin top-left, Y down)
+    /// into level space.
+    pub fn to_level_space(&self, x: f32, y: f32) -> Vec2 {
+        let half = self.pixel_size() / 2.;
+        Vec2::new(x - half.x, half.y - y)
+    }
+}
+
+/// Read an optional whole number property from a Tiled object.
+pub fn u32_property(properties: &tiled::Properties, name: &str) -> Option<u32> {
+    match properties.get(name)? {
+        tiled::PropertyValue::IntValue(n) => Some((*n).max(0) as u32),
+        value => {
+            log::warn!("Property {name} should be an int, got {value:?}");
+            None
+        }
+    }
+}
+
+/// Read an optional string property from a Tiled object.
+pub fn string_property<'a>(properties: &'a tiled::Properties, name: &str) -> Option<&'a str> {
+    match properties.get(name)? {
+        tiled::PropertyValue::StringValue(s) => Some(s.as_str()),
+        value => {
+            log::warn!("Property {name} should be a string, got {value:?}");
+            None
+        }
+    }
+}
+
+/// Read an optional number property from a Tiled object or tile. Whole
+/// numbers are accepted too.
+pub fn f32_property(properties: &tiled::Properties, name: &str) -> Option<f32> {
+    match properties.get(name)? {
+        tiled::PropertyValue::FloatValue(n) => Some(*n),
+        tiled::PropertyValue::IntValue(n) => Some(*n as f32),
+        value => {
+            log::warn!("Property {name} should be a float, got {value:?}");
+            None
+        }
+    }
+}
+
+/// Read an optional true/false property from a Tiled object or tile.
+pub fn bool_property(properties: &tiled::Properties, name: &str) -> Option<bool> {
+    match properties.get(name)? {
+        tiled::PropertyValue::BoolValue(b) => Some(*b),
+        value => {
+            log::warn!("Property {name} should be a bool, got {value:?}");
+            None
         }
     }
 }
 
 impl Levels {
+    pub fn new(levels: Vec<Level>) -> Self {
+        Self { levels }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Level> {
+        self.levels.iter()
+    }
+
     pub fn current(&self, level: CurrentLevel) -> Option<&Level> {
         self.levels.get(level.0 as usize)
     }
@@ -458,3 +793,134 @@ fn on_game_completed(
     commands.trigger(FadeOut { duration: 0.5 });
     next_state.set(LevelState::CompletedFadeOut);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Load a 4x4 map of 32 pixel tiles with `objects` in its object layer.
+    fn layout_with_objects(objects: &str) -> LevelLayout {
+        let tmx = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.10" orientation="orthogonal" renderorder="right-down" width="4" height="4" tilewidth="32" tileheight="32" infinite="0" nextlayerid="3" nextobjectid="10">
+ <layer id="1" name="Tile Layer 1" width="4" height="4">
+  <data encoding="csv">
+0,0,0,0,
+0,0,0,0,
+0,0,0,0,
+0,0,0,0
+</data>
+ </layer>
+ <objectgroup id="2" name="{OBJECT_LAYER_NAME}">
+{objects}
+ </objectgroup>
+</map>
+"#
+        );
+        // Read the map from memory, whatever path it is loaded from.
+        let reader = move |_: &std::path::Path| {
+            Ok::<_, std::io::Error>(std::io::Cursor::new(tmx.clone().into_bytes()))
+        };
+        let map = tiled::Loader::with_reader(reader)
+            .load_tmx_map("test.tmx")
+            .unwrap();
+        LevelLayout::from_map(&map)
+    }
+
+    #[test]
+    fn objects_are_placed_in_level_space() {
+        let layout = layout_with_objects(
+            r#"<object id="1" name="PlayerStart" x="16" y="16"><point/></object>
+<object id="2" name="Duckling" x="112" y="112"><point/></object>"#,
+        );
+
+        assert_eq!(layout.size, UVec2::new(4, 4));
+        assert_eq!(layout.bounds(), Rect::new(-64.0, -64.0, 64.0, 64.0));
+        assert_eq!(layout.player_start, Vec2::new(-48.0, 48.0));
+        assert_eq!(layout.ducklings[0].position, Vec2::new(48.0, -48.0));
+    }
+
+    #[test]
+    fn missing_properties_use_defaults() {
+        let layout = layout_with_objects(
+            r#"<object id="1" name="Duckling" x="16" y="16"><point/></object>
+<object id="2" name="Exit" x="48" y="16"><point/></object>
+<object id="3" name="Key" x="80" y="16"><point/></object>
+<object id="4" name="Switch" x="112" y="16"><point/></object>"#,
+        );
+
+        assert_eq!(layout.player_start, Vec2::ZERO);
+        assert_eq!(layout.ducklings[0].kind, DucklingKind::Normal);
+        assert_eq!(layout.exits[0].ducklings_required, None);
+        assert_eq!(layout.keys[0].color, KeyColor::Red);
+        // A switch that doesn't do anything is left out.
+        assert!(layout.switches.is_empty());
+    }
+
+    #[test]
+    fn unknown_property_values_use_defaults() {
+        let layout = layout_with_objects(
+            r#"<object id="1" name="Duckling" x="16" y="16">
+ <properties><property name="kind" value="purple"/></properties>
+ <point/>
+</object>
+<object id="2" name="Exit" x="48" y="16">
+ <properties><property name="ducklings_required" value="lots"/></properties>
+ <point/>
+</object>
+<object id="3" name="Key" x="80" y="16">
+ <properties><property name="color" value="mauve"/></properties>
+ <point/>
+</object>
+<object id="4" name="Switch" x="112" y="16">
+ <properties><property name="action" value="explode"/></properties>
+ <point/>
+</object>"#,
+        );
+
+        assert_eq!(layout.ducklings[0].kind, DucklingKind::Normal);
+        assert_eq!(layout.exits[0].ducklings_required, None);
+        assert_eq!(layout.keys[0].color, KeyColor::Red);
+        assert!(layout.switches.is_empty());
+    }
+
+    #[test]
+    fn properties_are_read() {
+        let layout = layout_with_objects(
+            r#"<object id="1" name="Duckling" x="16" y="16">
+ <properties><property name="kind" value="Golden"/></properties>
+ <point/>
+</object>
+<object id="2" name="Exit" x="48" y="16">
+ <properties><property name="ducklings_required" type="int" value="3"/></properties>
+ <point/>
+</object>
+<object id="3" name="Key" x="80" y="16">
+ <properties><property name="color" value="blue"/></properties>
+ <point/>
+</object>"#,
+        );
+
+        assert_eq!(layout.ducklings[0].kind, DucklingKind::Golden);
+        assert_eq!(layout.exits[0].ducklings_required, Some(3));
+        assert_eq!(layout.keys[0].color, KeyColor::Blue);
+    }
+
+    #[test]
+    fn unknown_objects_are_skipped() {
+        let layout = layout_with_objects(
+            r#"<object id="1" name="Trampoline" x="16" y="16"><point/></object>
+<object id="2" name="Duckling" x="48" y="16"><point/></object>"#,
+        );
+
+        let empty = layout_with_objects("");
+        assert_eq!(layout.ducklings.len(), 1);
+        assert_eq!(
+            layout,
+            LevelLayout {
+                ducklings: layout.ducklings.clone(),
+                ..empty
+            }
+        );
+    }
+}
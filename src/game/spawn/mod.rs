@@ -5,9 +5,25 @@
 use bevy::prelude::*;
 
 pub mod duckling;
+pub mod exit;
+pub mod fox;
+pub mod key;
 pub mod level;
+pub mod platform;
 pub mod player;
+pub mod spikes;
+pub mod switch;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins((level::plugin, player::plugin, duckling::plugin));
+    app.add_plugins((
+        level::plugin,
+        player::plugin,
+        duckling::plugin,
+        fox::plugin,
+        exit::plugin,
+        spikes::plugin,
+        platform::plugin,
+        key::plugin,
+        switch::plugin,
+    ));
 }
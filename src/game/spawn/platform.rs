@@ -0,0 +1,170 @@
+//! Spawn moving platforms. Platforms are part of the level, and move along
+//! their path in level space while the whole level rotates around them.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::game::{
+    assets::{HandleMap, ImageKey},
+    simulation::TICK_SECS,
+};
+
+use super::level::{LevelMarker, PlatformSpawnPoint};
+
+pub(super) fn plugin(app: &mut App) {
+    app.observe(spawn_platform);
+    app.register_type::<Platform>();
+}
+
+#[derive(Event, Debug)]
+pub struct SpawnPlatform;
+
+/// Platform thickness in pixels.
+const PLATFORM_HEIGHT: f32 = 16.0;
+/// Width of one plank of the platform in pixels, the same as a tile.
+const PLANK_WIDTH: f32 = 32.0;
+
+#[derive(Component, Debug, Clone, PartialEq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Platform {
+    path: Vec<Vec2>,
+    closed: bool,
+    speed: f32,
+    /// In radians per second.
+    rotation_speed: f32,
+    /// How far along the path the platform is.
+    distance: f32,
+    /// 1 when going forwards along the path, -1 on the way back.
+    direction: f32,
+    /// How far the platform moved in the last step, in world space.
+    step_translation: Vec2,
+    /// How far the platform turned in the last step, in radians.
+    step_rotation: f32,
+}
+
+impl Platform {
+    fn segments(&self) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+        let closing = match (self.closed, self.path.first(), self.path.last()) {
+            (true, Some(&first), Some(&last)) => Some((last, first)),
+            _ => None,
+        };
+        self.path
+            .windows(2)
+            .map(|pair| (pair[0], pair[1]))
+            .chain(closing)
+    }
+
+    fn length(&self) -> f32 {
+        self.segments().map(|(a, b)| a.distance(b)).sum()
+    }
+
+    /// The platform's position in level space.
+    fn position(&self) -> Vec2 {
+        let mut remaining = self.distance;
+        for (a, b) in self.segments() {
+            let length = a.distance(b);
+            if remaining <= length && length > 0.0 {
+                return a.lerp(b, remaining / length);
+            }
+            remaining -= length;
+        }
+        self.path.last().copied().unwrap_or_default()
+    }
+
+    fn advance(&mut self, delta_seconds: f32) {
+        let length = self.length();
+        if length <= 0.0 {
+            return;
+        }
+
+        self.distance += self.speed * self.direction * delta_seconds;
+        if self.closed {
+            self.distance = self.distance.rem_euclid(length);
+        } else if self.distance > length {
+            // Turn around at the ends of an open path.
+            self.distance = 2.0 * length - self.distance;
+            self.direction = -1.0;
+        } else if self.distance < 0.0 {
+            self.distance = -self.distance;
+            self.direction = 1.0;
+        }
+    }
+
+    /// How far something at `position` is carried along by the platform in
+    /// the last step. `center` is the platform's position, both in world space.
+    pub fn carry(&self, center: Vec2, position: Vec2) -> Vec2 {
+        let offset = position - center;
+        self.step_translation + Vec2::from_angle(self.step_rotation).rotate(offset) - offset
+    }
+}
+
+/// The platform a character is standing on, if any.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GroundPlatform(pub Option<Entity>);
+
+fn spawn_platform(
+    _trigger: Trigger<SpawnPlatform>,
+    mut commands: Commands,
+    image_handles: Res<HandleMap<ImageKey>>,
+    spawn_points: Query<(&PlatformSpawnPoint, &Transform, &Parent)>,
+) {
+    for (spawn_point, transform, level) in spawn_points.iter() {
+        let layout = &spawn_point.0;
+        let planks = layout.width.max(1);
+        let width = planks as f32 * PLANK_WIDTH;
+        commands.entity(level.get()).with_children(|parent| {
+            parent
+                .spawn((
+                    Name::new("Platform"),
+                    Platform {
+                        path: layout.path.clone(),
+                        closed: layout.closed,
+                        speed: layout.speed,
+                        rotation_speed: layout.rotation_speed.to_radians(),
+                        direction: 1.0,
+                        ..default()
+                    },
+                    SpatialBundle::from_transform(*transform),
+                    RigidBody::KinematicPositionBased,
+                    Collider::cuboid(width / 2.0, PLATFORM_HEIGHT / 2.0),
+                ))
+                .with_children(|platform| {
+                    for i in 0..planks {
+                        let x = (i as f32 + 0.5) * PLANK_WIDTH - width / 2.0;
+                        platform.spawn(SpriteBundle {
+                            texture: image_handles[&ImageKey::Platform].clone_weak(),
+                            transform: Transform::from_xyz(x, 0.0, 0.0),
+                            ..default()
+                        });
+                    }
+                });
+        });
+    }
+}
+
+/// Move platforms along their paths. This has to happen before the player
+/// moves, so that they can be carried along.
+pub fn move_platforms(
+    level_query: Query<&Transform, (With<LevelMarker>, Without<Platform>)>,
+    mut platform_query: Query<(&mut Platform, &mut Transform)>,
+) {
+    let Ok(level_transform) = level_query.get_single() else {
+        return;
+    };
+
+    let delta_seconds = TICK_SECS;
+    for (mut platform, mut transform) in &mut platform_query {
+        let before = transform.translation.truncate();
+        platform.advance(delta_seconds);
+        let after = platform.position();
+        let turn = platform.rotation_speed * delta_seconds;
+
+        transform.translation = after.extend(transform.translation.z);
+        transform.rotate_z(turn);
+
+        // The path is in level space, but the player moves in world space.
+        platform.step_translation =
+            (level_transform.rotation * (after - before).extend(0.0)).truncate();
+        platform.step_rotation = turn;
+    }
+}
@@ -5,17 +5,20 @@ use bevy_rapier2d::prelude::*;
 
 use crate::{
     game::{
-        animation::PlayerAnimation,
-        assets::{HandleMap, ImageKey},
+        animation::{AnimationParams, SpriteAnimator},
+        assets::{AnimationKey, HandleMap, ImageKey},
+        conga::CongaLine,
+        gravity::GravityFrame,
         movement::{
-            Movement, MovementController, PreviousPhysicalTranslation, SpriteOffset,
-            VisualTranslation,
+            JumpState, Movement, MovementController, PreviousPhysicalTranslation, SpriteOffset,
+            VisualTranslation, WallContact,
         },
+        tiles::GroundTile,
     },
     screen::Screen,
 };
 
-use super::level::PlayerSpawnPoint;
+use super::{level::PlayerSpawnPoint, platform::GroundPlatform};
 
 pub(super) fn plugin(app: &mut App) {
     app.observe(spawn_player);
@@ -50,6 +53,7 @@ fn spawn_player(
     _trigger: Trigger<SpawnPlayer>,
     mut commands: Commands,
     image_handles: Res<HandleMap<ImageKey>>,
+    animation_handles: Res<HandleMap<AnimationKey>>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
     spawn_point: Query<&Transform, With<PlayerSpawnPoint>>,
 ) {
@@ -57,9 +61,8 @@ fn spawn_player(
     // By attaching it to a [`SpriteBundle`] and providing an index, we can specify which section of the image we want to see.
     // We will use this to animate our player character. You can learn more about texture atlases in this example:
     // https://github.com/bevyengine/bevy/blob/latest/examples/2d/texture_atlas.rs
-    let layout = TextureAtlasLayout::from_grid(UVec2::splat(32), 6, 2, Some(UVec2::splat(1)), None);
+    let layout = TextureAtlasLayout::from_grid(UVec2::splat(32), 6, 3, Some(UVec2::splat(1)), None);
     let texture_atlas_layout = texture_atlas_layouts.add(layout);
-    let player_animation = PlayerAnimation::new();
 
     let spawn_point = spawn_point.single();
     let startx = spawn_point.translation.x;
@@ -79,6 +82,7 @@ fn spawn_player(
             Movement {
                 speed: 200.0,
                 jump_speed: 400.0,
+                ..default()
             },
             StateScoped(Screen::Playing),
             Collider::ball(11.5),
@@ -91,11 +95,24 @@ fn spawn_player(
                 max_slope_climb_angle: 30_f32.to_radians(),
                 // Automatically slide down slopes smaller than this.
                 min_slope_slide_angle: 10_f32.to_radians(),
+                // Ducklings following the player are sensors.
+                filter_flags: QueryFilterFlags::EXCLUDE_SENSORS,
                 ..default()
             },
-            IsOnGround::default(),
-            PreviousPhysicalTranslation(Vec2::new(startx, starty)),
-            VisualTranslation(Vec2::new(startx, starty)),
+            (
+                IsOnGround::default(),
+                JumpState::default(),
+                GravityFrame::default(),
+                WallContact::default(),
+                GroundTile::default(),
+                GroundPlatform::default(),
+                AnimationParams::default(),
+            ),
+            (
+                PreviousPhysicalTranslation(Vec2::new(startx, starty)),
+                VisualTranslation(Vec2::new(startx, starty)),
+            ),
+            CongaLine::default(),
         ))
         .with_children(|parent| {
             parent.spawn((
@@ -106,9 +123,9 @@ fn spawn_player(
                 },
                 TextureAtlas {
                     layout: texture_atlas_layout.clone(),
-                    index: player_animation.get_atlas_index(),
+                    index: 0,
                 },
-                player_animation,
+                SpriteAnimator::new(animation_handles[&AnimationKey::Ducky].clone_weak()),
                 SpriteOffset(Vec2::new(0.0, 4.0)),
                 SpriteMarker,
             ));
@@ -0,0 +1,41 @@
+//! Spawn spikes. Spikes are solid, and hurt the player when they touch them.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::game::{
+    assets::{HandleMap, ImageKey},
+    hazard::Hazard,
+};
+
+use super::level::SpikesSpawnPoint;
+
+pub(super) fn plugin(app: &mut App) {
+    app.observe(spawn_spikes);
+}
+
+#[derive(Event, Debug)]
+pub struct SpawnSpikes;
+
+fn spawn_spikes(
+    _trigger: Trigger<SpawnSpikes>,
+    mut commands: Commands,
+    image_handles: Res<HandleMap<ImageKey>>,
+    spawn_points: Query<(&Transform, &Parent), With<SpikesSpawnPoint>>,
+) {
+    for (transform, level) in spawn_points.iter() {
+        // Spikes are part of the level so that they rotate with it.
+        commands.entity(level.get()).with_children(|parent| {
+            parent.spawn((
+                Name::new("Spikes"),
+                Hazard,
+                SpriteBundle {
+                    texture: image_handles[&ImageKey::Spikes].clone_weak(),
+                    transform: *transform,
+                    ..default()
+                },
+                Collider::cuboid(14.0, 14.0),
+            ));
+        });
+    }
+}
@@ -0,0 +1,69 @@
+//! Spawn switches. Switches are pressure plates that the player presses by
+//! walking into them, see [`crate::game::puzzle`].
+
+use bevy::prelude::*;
+
+use crate::{
+    game::{
+        assets::{HandleMap, ImageKey},
+        puzzle::SwitchAction,
+    },
+    AppSet,
+};
+
+use super::level::SwitchSpawnPoint;
+
+pub(super) fn plugin(app: &mut App) {
+    app.observe(spawn_switch);
+    app.register_type::<Switch>();
+    app.add_systems(Update, update_switch_sprites.in_set(AppSet::Update));
+}
+
+#[derive(Event, Debug)]
+pub struct SpawnSwitch;
+
+/// How bright a switch is drawn while it is off.
+const OFF_BRIGHTNESS: f32 = 0.6;
+
+#[derive(Component, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct Switch {
+    pub action: SwitchAction,
+    /// Flips every time the switch is pressed.
+    pub on: bool,
+    /// Whether the player is standing on the switch right now.
+    pub held: bool,
+}
+
+fn spawn_switch(
+    _trigger: Trigger<SpawnSwitch>,
+    mut commands: Commands,
+    image_handles: Res<HandleMap<ImageKey>>,
+    spawn_points: Query<(&SwitchSpawnPoint, &Transform, &Parent)>,
+) {
+    for (spawn_point, transform, level) in spawn_points.iter() {
+        // Switches are part of the level so that they rotate with it.
+        commands.entity(level.get()).with_children(|parent| {
+            parent.spawn((
+                Name::new("Switch"),
+                Switch {
+                    action: spawn_point.action.clone(),
+                    on: false,
+                    held: false,
+                },
+                SpriteBundle {
+                    texture: image_handles[&ImageKey::Switch].clone_weak(),
+                    transform: *transform,
+                    ..default()
+                },
+            ));
+        });
+    }
+}
+
+fn update_switch_sprites(mut query: Query<(&Switch, &mut Sprite), Changed<Switch>>) {
+    for (switch, mut sprite) in &mut query {
+        let brightness = if switch.on { 1.0 } else { OFF_BRIGHTNESS };
+        sprite.color = Color::srgb(brightness, brightness, brightness);
+    }
+}
@@ -0,0 +1,147 @@
+//! Special tiles, set up with custom tile properties in `Tileset1.tsx`:
+//!
+//! - `restitution` (float): how much of the landing speed bounces back up.
+//! - `friction` (float): below 1 the tile is slippery, above 1 it is sticky.
+//! - `hazard` (bool): touching the tile hurts the player, see [`super::hazard`].
+//! - `conveyor_speed` (float): carries whatever stands on it along the tile,
+//!   in pixels per second. Positive is to the right.
+//! - `door` (string): the color of the key that opens the tile, see
+//!   [`super::puzzle`].
+//!
+//! The properties are copied onto the collider entities that `bevy_ecs_tiled`
+//! spawns for each tile, and the player's movement reacts to them.
+
+use bevy::prelude::*;
+use bevy_ecs_tiled::prelude::*;
+use bevy_ecs_tilemap::prelude::TilePos;
+use bevy_rapier2d::prelude::Collider;
+
+use super::{
+    hazard::Hazard,
+    puzzle::{Door, KeyColor},
+    spawn::level::{bool_property, f32_property, string_property},
+};
+use crate::AppSet;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<TileProperties>();
+    app.add_systems(FixedUpdate, tag_tile_colliders.before(AppSet::TickTimers));
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct TileProperties {
+    pub restitution: f32,
+    pub friction: f32,
+    pub hazard: bool,
+    pub conveyor_speed: f32,
+    pub door: Option<KeyColor>,
+}
+
+impl Default for TileProperties {
+    fn default() -> Self {
+        Self {
+            restitution: 0.0,
+            friction: 1.0,
+            hazard: false,
+            conveyor_speed: 0.0,
+            door: None,
+        }
+    }
+}
+
+impl TileProperties {
+    pub fn from_properties(properties: &tiled::Properties) -> Self {
+        let default = Self::default();
+        Self {
+            restitution: f32_property(properties, "restitution").unwrap_or(default.restitution),
+            friction: f32_property(properties, "friction").unwrap_or(default.friction),
+            hazard: bool_property(properties, "hazard").unwrap_or(default.hazard),
+            conveyor_speed: f32_property(properties, "conveyor_speed")
+                .unwrap_or(default.conveyor_speed),
+            door: string_property(properties, "door").and_then(|name| {
+                let color = KeyColor::from_name(name);
+                if color.is_none() {
+                    log::warn!("Unknown door color: {name}");
+                }
+                color
+            }),
+        }
+    }
+
+    pub fn is_slippery(&self) -> bool {
+        self.friction < 1.0
+    }
+
+    /// How much of the usual walking and jumping speed is left on this tile.
+    /// Sticky tiles slow everything down.
+    pub fn speed_factor(&self) -> f32 {
+        1.0 / self.friction.max(1.0)
+    }
+}
+
+/// The tile a character is standing on. Plain ground if it isn't standing
+/// on anything special.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default)]
+pub struct GroundTile {
+    pub properties: TileProperties,
+    /// The direction along the top of the tile in world space. This turns
+    /// along with the level.
+    pub along: Vec2,
+}
+
+/// Give each new tile collider the properties of its tile. Colliders are
+/// spawned as children of their tile, somewhere below the map entity. Other
+/// colliders are only looked at once, when they are added.
+///
+/// This runs at the start of each simulation tick, so tiles have their
+/// properties from the first tick they take part in however the frames fall.
+fn tag_tile_colliders(
+    mut commands: Commands,
+    maps: Res<Assets<TiledMap>>,
+    collider_query: Query<Entity, Added<Collider>>,
+    parent_query: Query<&Parent>,
+    tile_query: Query<&TilePos>,
+    map_query: Query<&Handle<TiledMap>>,
+) {
+    for entity in &collider_query {
+        let mut tile_pos = None;
+        let mut map = None;
+        for ancestor in parent_query.iter_ancestors(entity) {
+            if let Ok(pos) = tile_query.get(ancestor) {
+                tile_pos = Some(*pos);
+            }
+            if let Ok(handle) = map_query.get(ancestor) {
+                map = maps.get(handle);
+                break;
+            }
+        }
+        let (Some(tile_pos), Some(map)) = (tile_pos, map) else {
+            continue;
+        };
+
+        let properties = tile_properties(&map.map, tile_pos);
+        let mut collider = commands.entity(entity);
+        collider.insert(properties);
+        if properties.hazard {
+            collider.insert(Hazard);
+        }
+        if let Some(color) = properties.door {
+            collider.insert(Door(color));
+        }
+    }
+}
+
+/// Look up the properties of the tile at `pos` on any tile layer. Tile
+/// positions count up from the bottom of the map, Tiled counts down from the top.
+fn tile_properties(map: &tiled::Map, pos: TilePos) -> TileProperties {
+    let x = pos.x as i32;
+    let y = map.height as i32 - 1 - pos.y as i32;
+    map.layers()
+        .filter_map(|layer| layer.as_tile_layer())
+        .filter_map(|layer| layer.get_tile(x, y))
+        .filter_map(|layer_tile| layer_tile.get_tile())
+        .map(|tile| TileProperties::from_properties(&tile.properties))
+        .find(|properties| *properties != TileProperties::default())
+        .unwrap_or_default()
+}
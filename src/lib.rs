@@ -27,6 +27,12 @@ impl Plugin for AppPlugin {
             Update,
             (AppSet::TickTimers, AppSet::RecordInput, AppSet::Update).chain(),
         );
+        // The simulation uses the same sets. Physics steps straight after it,
+        // in `FixedPostUpdate`.
+        app.configure_sets(
+            FixedUpdate,
+            (AppSet::TickTimers, AppSet::RecordInput, AppSet::Update).chain(),
+        );
 
         // Spawn the main camera.
         app.add_systems(Startup, spawn_camera);
@@ -64,7 +70,9 @@ impl Plugin for AppPlugin {
         // Add other plugins.
         app.add_plugins(TilemapPlugin)
             .add_plugins(TiledMapPlugin)
-            .add_plugins(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(32.0));
+            .add_plugins(
+                RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(32.0).in_fixed_schedule(),
+            );
 
         // #[cfg(feature = "dev")]
         // app.add_plugins(RapierDebugRenderPlugin::default());
@@ -81,7 +89,8 @@ impl Plugin for AppPlugin {
     }
 }
 
-/// High-level groupings of systems for the app in the `Update` schedule.
+/// High-level groupings of systems for the app in the `Update` and
+/// `FixedUpdate` schedules.
 /// When adding a new variant, make sure to order it in the `configure_sets`
 /// call above.
 #[derive(SystemSet, Debug, Clone, Copy, Eq, PartialEq, Hash)]
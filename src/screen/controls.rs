@@ -0,0 +1,209 @@
+//! A controls screen that can be accessed from the title screen.
+//! Each action can be rebound to a new key or gamepad button from here.
+
+use bevy::prelude::*;
+
+#[cfg(feature = "dev")]
+use bevy::dev_tools::states::log_transitions;
+
+use super::Screen;
+use crate::{
+    game::input::{Action, Binding, InputMap},
+    ui::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_state::<ControlsState>();
+    // The gamepad button pressed to rebind an action shouldn't also press
+    // whichever button has focus.
+    app.configure_sets(Update, GamepadNavigation.run_if(not(is_rebinding)));
+    app.add_systems(OnEnter(Screen::Controls), enter_controls);
+    app.add_systems(OnExit(Screen::Controls), exit_controls);
+
+    app.register_type::<ControlsAction>();
+    app.add_systems(
+        Update,
+        (
+            capture_binding,
+            handle_controls_action,
+            update_binding_labels
+                .run_if(state_changed::<ControlsState>.or_else(resource_changed::<InputMap>)),
+        )
+            .chain()
+            .run_if(in_state(Screen::Controls)),
+    );
+
+    #[cfg(feature = "dev")]
+    app.add_systems(Update, log_transitions::<ControlsState>);
+}
+
+#[derive(States, Debug, Hash, PartialEq, Eq, Clone, Default)]
+enum ControlsState {
+    #[default]
+    Inactive,
+    Active,
+    /// Waiting for the player to press the new key or button for an action.
+    Rebinding(Action),
+}
+
+fn is_rebinding(state: Res<State<ControlsState>>) -> bool {
+    matches!(state.get(), ControlsState::Rebinding(_))
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum ControlsAction {
+    Rebind(Action),
+    CancelRebind,
+    ResetDefaults,
+    Back,
+}
+
+fn enter_controls(
+    mut commands: Commands,
+    input_map: Res<InputMap>,
+    mut next_state: ResMut<NextState<ControlsState>>,
+) {
+    commands
+        .ui_root()
+        .insert(StateScoped(Screen::Controls))
+        .with_children(|children| {
+            children.header("Controls");
+            children
+                .spawn((
+                    Name::new("Action Grid"),
+                    NodeBundle {
+                        style: Style {
+                            width: Val::Px(900.0),
+                            flex_wrap: FlexWrap::Wrap,
+                            justify_content: JustifyContent::Center,
+                            column_gap: Val::Px(10.0),
+                            row_gap: Val::Px(10.0),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                ))
+                .with_children(|grid| {
+                    for action in Action::ALL {
+                        grid.card(action.label(), bindings_label(&input_map, action), true)
+                            .insert(ControlsAction::Rebind(action));
+                    }
+                });
+            children.label("Choose an action, then press a key or gamepad button to bind it.");
+            children
+                .button("Cancel")
+                .insert(ControlsAction::CancelRebind);
+            children
+                .button("Reset to Defaults")
+                .insert(ControlsAction::ResetDefaults);
+            children
+                .button("Back")
+                .insert((ControlsAction::Back, BackButton));
+        });
+
+    next_state.set(ControlsState::Active);
+}
+
+fn exit_controls(mut next_state: ResMut<NextState<ControlsState>>) {
+    next_state.set(ControlsState::Inactive);
+}
+
+fn handle_controls_action(
+    state: Res<State<ControlsState>>,
+    mut input_map: ResMut<InputMap>,
+    mut next_state: ResMut<NextState<ControlsState>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut button_query: InteractionQuery<&ControlsAction>,
+) {
+    for (interaction, action) in &mut button_query {
+        if !matches!(interaction, Interaction::Pressed) {
+            continue;
+        }
+
+        // Keys and gamepad buttons pressed while rebinding belong to the new
+        // binding, so cancelling is only done by clicking Cancel.
+        match (state.get(), action) {
+            (ControlsState::Active, ControlsAction::Rebind(action)) => {
+                next_state.set(ControlsState::Rebinding(*action));
+            }
+            (ControlsState::Active, ControlsAction::ResetDefaults) => {
+                *input_map = InputMap::default();
+            }
+            (ControlsState::Active, ControlsAction::Back) => next_screen.set(Screen::Title),
+            (ControlsState::Rebinding(_), ControlsAction::CancelRebind) => {
+                next_state.set(ControlsState::Active);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Bind the first key or gamepad button pressed while rebinding.
+///
+/// The state only changes on the frame after the action was chosen, so the
+/// press that chose it is never picked up as the new binding.
+fn capture_binding(
+    state: Res<State<ControlsState>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    mut input_map: ResMut<InputMap>,
+    mut next_state: ResMut<NextState<ControlsState>>,
+) {
+    let ControlsState::Rebinding(action) = *state.get() else {
+        return;
+    };
+
+    let binding = keys
+        .get_just_pressed()
+        .next()
+        .map(|key| Binding::Key(*key))
+        .or_else(|| {
+            gamepad_buttons
+                .get_just_pressed()
+                .next()
+                .map(|button| Binding::Gamepad(button.button_type))
+        });
+    if let Some(binding) = binding {
+        input_map.rebind(action, binding);
+        next_state.set(ControlsState::Active);
+    }
+}
+
+fn update_binding_labels(
+    state: Res<State<ControlsState>>,
+    input_map: Res<InputMap>,
+    card_query: Query<(&ControlsAction, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (action, children) in &card_query {
+        let ControlsAction::Rebind(action) = *action else {
+            continue;
+        };
+        // The details are the second line of the card.
+        let Some(mut text) = children
+            .get(1)
+            .and_then(|child| text_query.get_mut(*child).ok())
+        else {
+            continue;
+        };
+
+        text.sections[0].value = if *state.get() == ControlsState::Rebinding(action) {
+            "Press a key or button...".to_string()
+        } else {
+            bindings_label(&input_map, action)
+        };
+    }
+}
+
+fn bindings_label(input_map: &InputMap, action: Action) -> String {
+    let bindings = input_map.bindings(action);
+    if bindings.is_empty() {
+        return "Unbound".to_string();
+    }
+    bindings
+        .iter()
+        .map(Binding::label)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -39,7 +39,9 @@ fn enter_credits(mut commands: Commands) {
             children.label("Ducky sprite - CC0 by Caz Creates Games");
             children.label("Music - CC BY 3.0 by Kevin MacLeod");
 
-            children.button("Back").insert(CreditsAction::Back);
+            children
+                .button("Back")
+                .insert((CreditsAction::Back, BackButton));
         });
 
     commands.trigger(PlaySoundtrack::Key(SoundtrackKey::Credits));
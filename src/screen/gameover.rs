@@ -4,7 +4,10 @@ use bevy::prelude::*;
 
 use super::Screen;
 use crate::{
-    game::{assets::SoundtrackKey, audio::soundtrack::PlaySoundtrack, score::OverallScore},
+    game::{
+        assets::SoundtrackKey, audio::soundtrack::PlaySoundtrack, progress::Progress,
+        score::OverallScore,
+    },
     systems::fade::FadeIn,
     ui::prelude::*,
 };
@@ -26,7 +29,11 @@ enum GameOverAction {
     Menu,
 }
 
-fn enter_gameover(mut commands: Commands, overall_score: Res<OverallScore>) {
+fn enter_gameover(
+    mut commands: Commands,
+    overall_score: Res<OverallScore>,
+    progress: Res<Progress>,
+) {
     commands
         .ui_root()
         .insert(StateScoped(Screen::GameOver))
@@ -41,6 +48,9 @@ fn enter_gameover(mut commands: Commands, overall_score: Res<OverallScore>) {
             children.label(" ");
             children.label("seconds");
             children.label(" ");
+            if let Some(best_run) = progress.best_run {
+                children.label(format!("Best run: {:.1} seconds", best_run));
+            }
             children.label(" ");
             children.button("Continue").insert(GameOverAction::Menu);
         });
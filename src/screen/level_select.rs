@@ -0,0 +1,173 @@
+//! A level select screen that can be accessed from the title screen.
+//! Only levels that have been unlocked can be started from here, and levels
+//! with a saved replay can be watched.
+
+use bevy::prelude::*;
+
+#[cfg(feature = "dev")]
+use bevy::dev_tools::states::log_transitions;
+
+use super::{playing::StartingLevel, Screen};
+use crate::{
+    game::{
+        progress::Progress,
+        replay::{load_replay, ReplayPlayback},
+        simulation::TICKS_PER_SECOND,
+        spawn::level::Levels,
+    },
+    systems::fade::{FadeCompleted, FadeIn, FadeOut},
+    ui::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_state::<LevelSelectState>();
+    app.add_systems(OnEnter(Screen::LevelSelect), enter_level_select);
+    app.add_systems(OnExit(Screen::LevelSelect), exit_level_select);
+
+    app.register_type::<LevelSelectAction>();
+    app.add_systems(
+        Update,
+        (handle_level_select_action, on_fade_completed).run_if(in_state(Screen::LevelSelect)),
+    );
+
+    #[cfg(feature = "dev")]
+    app.add_systems(Update, log_transitions::<LevelSelectState>);
+}
+
+#[derive(States, Debug, Hash, PartialEq, Eq, Clone, Default)]
+enum LevelSelectState {
+    #[default]
+    Inactive,
+    Active,
+    ActionPlayFadingOut,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum LevelSelectAction {
+    Play(usize),
+    /// Watch the saved replay for a level.
+    Watch(usize),
+    Back,
+}
+
+fn enter_level_select(
+    mut commands: Commands,
+    levels: Res<Levels>,
+    progress: Res<Progress>,
+    mut next_state: ResMut<NextState<LevelSelectState>>,
+) {
+    commands
+        .ui_root()
+        .insert(StateScoped(Screen::LevelSelect))
+        .with_children(|children| {
+            children.header("Select Level");
+            children
+                .spawn((
+                    Name::new("Level Grid"),
+                    NodeBundle {
+                        style: Style {
+                            width: Val::Px(900.0),
+                            flex_wrap: FlexWrap::Wrap,
+                            justify_content: JustifyContent::Center,
+                            column_gap: Val::Px(10.0),
+                            row_gap: Val::Px(10.0),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                ))
+                .with_children(|grid| {
+                    for (index, level) in levels.iter().enumerate() {
+                        let title = format!("{}. {}", index + 1, level.name);
+                        if !progress.is_unlocked(index) {
+                            grid.card(title, "Locked", false);
+                            continue;
+                        }
+
+                        let record = progress.record(index);
+                        let details = match record.best_ticks {
+                            Some(best_ticks) => format!(
+                                "Best: {:.1}s  Ducklings: {} / {}  Score: {}",
+                                best_ticks as f64 / TICKS_PER_SECOND,
+                                record.ducklings_collected,
+                                record.ducklings_total,
+                                record.best_score
+                            ),
+                            None => "Not completed".to_string(),
+                        };
+                        grid.card(title, details, true)
+                            .insert(LevelSelectAction::Play(index));
+
+                        if let Some(replay) = load_replay(index) {
+                            let secs = replay.ticks() as f64 / TICKS_PER_SECOND;
+                            grid.card(
+                                format!("Watch {}", index + 1),
+                                format!("Fastest run: {secs:.1}s"),
+                                true,
+                            )
+                            .insert(LevelSelectAction::Watch(index));
+                        }
+                    }
+                });
+            children
+                .button("Back")
+                .insert((LevelSelectAction::Back, BackButton));
+        });
+
+    // Coming back from watching a replay leaves the screen fading out.
+    commands.trigger(FadeIn { duration: 0.5 });
+    next_state.set(LevelSelectState::Active);
+}
+
+fn exit_level_select(mut next_state: ResMut<NextState<LevelSelectState>>) {
+    next_state.set(LevelSelectState::Inactive);
+}
+
+fn handle_level_select_action(
+    mut commands: Commands,
+    state: Res<State<LevelSelectState>>,
+    mut starting_level: ResMut<StartingLevel>,
+    mut next_state: ResMut<NextState<LevelSelectState>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut button_query: InteractionQuery<&LevelSelectAction>,
+) {
+    if *state.get() != LevelSelectState::Active {
+        return;
+    }
+
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                LevelSelectAction::Play(index) => {
+                    starting_level.0 = *index;
+                    next_state.set(LevelSelectState::ActionPlayFadingOut);
+                    commands.trigger(FadeOut { duration: 0.5 });
+                }
+                LevelSelectAction::Watch(index) => {
+                    let Some(replay) = load_replay(*index) else {
+                        continue;
+                    };
+                    let playback = ReplayPlayback::new(replay);
+                    starting_level.0 = playback.level();
+                    commands.insert_resource(playback);
+                    next_state.set(LevelSelectState::ActionPlayFadingOut);
+                    commands.trigger(FadeOut { duration: 0.5 });
+                }
+                LevelSelectAction::Back => next_screen.set(Screen::Title),
+            }
+        }
+    }
+}
+
+fn on_fade_completed(
+    mut events: EventReader<FadeCompleted>,
+    state: Res<State<LevelSelectState>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    for _ in events.read() {
+        if *state.get() == LevelSelectState::ActionPlayFadingOut {
+            next_screen.set(Screen::Playing);
+        }
+    }
+}
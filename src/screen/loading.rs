@@ -1,11 +1,14 @@
 //! A loading screen during which game assets are loaded.
 //! This reduces stuttering, especially for audio on WASM.
 
-use bevy::prelude::*;
+use bevy::{asset::LoadState, prelude::*};
 
 use super::Screen;
 use crate::{
-    game::assets::{HandleMap, ImageKey, SfxKey, SoundtrackKey},
+    game::{
+        assets::{AnimationKey, HandleMap, ImageKey, SfxKey, SoundtrackKey},
+        campaign::CampaignHandle,
+    },
     ui::prelude::*,
 };
 
@@ -13,14 +16,21 @@ pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Loading), enter_loading);
     app.add_systems(
         Update,
-        continue_to_title.run_if(in_state(Screen::Loading).and_then(all_assets_loaded)),
+        (
+            continue_to_title.run_if(all_assets_loaded),
+            show_campaign_error.run_if(campaign_failed_to_load),
+        )
+            .run_if(in_state(Screen::Loading)),
     );
 }
 
+#[derive(Component)]
+struct LoadingScreen;
+
 fn enter_loading(mut commands: Commands) {
     commands
         .ui_root()
-        .insert(StateScoped(Screen::Loading))
+        .insert((LoadingScreen, StateScoped(Screen::Loading)))
         .with_children(|children| {
             children.label("Loading...");
         });
@@ -31,10 +41,52 @@ fn all_assets_loaded(
     image_handles: Res<HandleMap<ImageKey>>,
     sfx_handles: Res<HandleMap<SfxKey>>,
     soundtrack_handles: Res<HandleMap<SoundtrackKey>>,
+    animation_handles: Res<HandleMap<AnimationKey>>,
+    campaign_handle: Res<CampaignHandle>,
 ) -> bool {
     image_handles.all_loaded(&asset_server)
         && sfx_handles.all_loaded(&asset_server)
         && soundtrack_handles.all_loaded(&asset_server)
+        && animation_handles.all_loaded(&asset_server)
+        && asset_server.is_loaded_with_dependencies(&campaign_handle.0)
+}
+
+fn campaign_failed_to_load(
+    asset_server: Res<AssetServer>,
+    campaign_handle: Res<CampaignHandle>,
+) -> bool {
+    matches!(
+        asset_server.get_load_state(&campaign_handle.0),
+        Some(LoadState::Failed(_))
+    )
+}
+
+/// Nothing can be played without the campaign, so say what went wrong
+/// rather than waiting for it forever.
+fn show_campaign_error(
+    mut commands: Commands,
+    mut shown: Local<bool>,
+    asset_server: Res<AssetServer>,
+    campaign_handle: Res<CampaignHandle>,
+    screen_query: Query<Entity, With<LoadingScreen>>,
+) {
+    if *shown {
+        return;
+    }
+    *shown = true;
+
+    if let Some(LoadState::Failed(error)) = asset_server.get_load_state(&campaign_handle.0) {
+        log::error!("Could not load the campaign: {error}");
+    }
+    for entity in &screen_query {
+        commands
+            .entity(entity)
+            .despawn_descendants()
+            .with_children(|children| {
+                children.label("Could not load the level list.");
+                children.label("Check assets/campaign.ron and restart the game.");
+            });
+    }
 }
 
 fn continue_to_title(mut next_screen: ResMut<NextState<Screen>>) {
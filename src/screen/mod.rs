@@ -1,8 +1,11 @@
 //! The game's main screen states and transitions between them.
 
+mod controls;
 mod credits;
 mod gameover;
+mod level_select;
 mod loading;
+mod pause;
 mod playing;
 mod splash;
 mod title;
@@ -18,7 +21,10 @@ pub(super) fn plugin(app: &mut App) {
         loading::plugin,
         title::plugin,
         credits::plugin,
+        controls::plugin,
+        level_select::plugin,
         playing::plugin,
+        pause::plugin,
         gameover::plugin,
     ));
 }
@@ -31,6 +37,8 @@ pub enum Screen {
     Loading,
     Title,
     Credits,
+    Controls,
+    LevelSelect,
     Playing,
     GameOver,
 }
@@ -0,0 +1,126 @@
+//! The pause menu, shown over the level while the game is paused.
+
+use bevy::prelude::*;
+
+#[cfg(feature = "dev")]
+use bevy::dev_tools::states::log_transitions;
+
+use super::{
+    playing::PlayingState,
+    title::{sound_label, SoundButton},
+    Screen,
+};
+use crate::{
+    game::{
+        settings::{GameSettings, ToggleSound},
+        spawn::level::{EndLevel, RestartLevel},
+    },
+    ui::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_sub_state::<PauseMenu>();
+    app.enable_state_scoped_entities::<PauseMenu>();
+    app.add_systems(OnEnter(PauseMenu::Main), enter_pause_menu);
+    app.add_systems(OnEnter(PauseMenu::Settings), enter_settings_menu);
+
+    app.register_type::<PauseAction>();
+    app.add_systems(
+        Update,
+        handle_pause_action.run_if(in_state(PlayingState::Paused)),
+    );
+
+    #[cfg(feature = "dev")]
+    app.add_systems(Update, log_transitions::<PauseMenu>);
+}
+
+/// Which page of the pause menu is showing.
+#[derive(SubStates, Debug, Hash, PartialEq, Eq, Clone, Default)]
+#[source(PlayingState = PlayingState::Paused)]
+enum PauseMenu {
+    #[default]
+    Main,
+    Settings,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum PauseAction {
+    Resume,
+    RestartLevel,
+    Settings,
+    SoundToggle,
+    BackToMenu,
+    QuitToTitle,
+}
+
+fn enter_pause_menu(mut commands: Commands) {
+    commands
+        .ui_root()
+        .insert((
+            StateScoped(PauseMenu::Main),
+            BackgroundColor(ui_palette::OVERLAY_BACKGROUND),
+        ))
+        .with_children(|children| {
+            children.header("Paused");
+            children
+                .button("Resume")
+                .insert((PauseAction::Resume, BackButton));
+            children
+                .button("Restart Level")
+                .insert(PauseAction::RestartLevel);
+            children.button("Settings").insert(PauseAction::Settings);
+            children
+                .button("Quit to Title")
+                .insert(PauseAction::QuitToTitle);
+        });
+}
+
+fn enter_settings_menu(mut commands: Commands, settings: Res<GameSettings>) {
+    commands
+        .ui_root()
+        .insert((
+            StateScoped(PauseMenu::Settings),
+            BackgroundColor(ui_palette::OVERLAY_BACKGROUND),
+        ))
+        .with_children(|children| {
+            children.header("Settings");
+            children
+                .button(sound_label(&settings))
+                .insert((PauseAction::SoundToggle, SoundButton));
+            children
+                .button("Back")
+                .insert((PauseAction::BackToMenu, BackButton));
+        });
+}
+
+fn handle_pause_action(
+    mut commands: Commands,
+    mut settings: ResMut<GameSettings>,
+    mut next_menu: ResMut<NextState<PauseMenu>>,
+    mut next_state: ResMut<NextState<PlayingState>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut button_query: InteractionQuery<&PauseAction>,
+) {
+    for (interaction, action) in &mut button_query {
+        if matches!(interaction, Interaction::Pressed) {
+            match action {
+                PauseAction::Resume => next_state.set(PlayingState::Running),
+                PauseAction::RestartLevel => {
+                    next_state.set(PlayingState::Running);
+                    commands.trigger(RestartLevel);
+                }
+                PauseAction::Settings => next_menu.set(PauseMenu::Settings),
+                PauseAction::SoundToggle => {
+                    settings.sound_enabled = !settings.sound_enabled;
+                    commands.trigger(ToggleSound);
+                }
+                PauseAction::BackToMenu => next_menu.set(PauseMenu::Main),
+                PauseAction::QuitToTitle => {
+                    commands.trigger(EndLevel);
+                    next_screen.set(Screen::Title);
+                }
+            }
+        }
+    }
+}
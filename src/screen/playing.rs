@@ -1,30 +1,61 @@
 //! The screen state for the main game loop.
 
-use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::RapierConfiguration;
+
+#[cfg(feature = "dev")]
+use bevy::dev_tools::states::log_transitions;
 
 use super::Screen;
 use crate::{
     game::{
         assets::SoundtrackKey,
         audio::soundtrack::PlaySoundtrack,
-        spawn::level::{EndLevel, StartNewGame},
+        input::{action_just_pressed, Action},
+        score::Score,
+        spawn::level::{RestartLevel, StartNewGame},
     },
     systems::fade::FadeIn,
 };
 
 pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<StartingLevel>();
+    app.add_sub_state::<PlayingState>();
     app.add_systems(OnEnter(Screen::Playing), enter_playing);
     app.add_systems(OnExit(Screen::Playing), exit_playing);
 
+    app.add_systems(OnEnter(PlayingState::Paused), pause_game);
+    app.add_systems(OnExit(PlayingState::Paused), resume_game);
+    app.add_systems(
+        Update,
+        toggle_pause.run_if(in_state(Screen::Playing).and_then(action_just_pressed(Action::Pause))),
+    );
+
     app.add_systems(
         Update,
-        return_to_title_screen
-            .run_if(in_state(Screen::Playing).and_then(input_just_pressed(KeyCode::Escape))),
+        restart_level
+            .run_if(in_state(PlayingState::Running).and_then(action_just_pressed(Action::Restart))),
     );
+
+    #[cfg(feature = "dev")]
+    app.add_systems(Update, log_transitions::<PlayingState>);
 }
 
-fn enter_playing(mut commands: Commands) {
-    commands.trigger(StartNewGame);
+/// The level to start from when entering [`Screen::Playing`].
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(super) struct StartingLevel(pub usize);
+
+/// Whether the game is running or paused. Only exists while playing.
+#[derive(SubStates, Debug, Hash, PartialEq, Eq, Clone, Default)]
+#[source(Screen = Screen::Playing)]
+pub(super) enum PlayingState {
+    #[default]
+    Running,
+    Paused,
+}
+
+fn enter_playing(mut commands: Commands, starting_level: Res<StartingLevel>) {
+    commands.trigger(StartNewGame(starting_level.0));
     commands.trigger(PlaySoundtrack::Key(SoundtrackKey::Gameplay));
     commands.trigger(FadeIn { duration: 0.5 })
 }
@@ -34,7 +65,36 @@ fn exit_playing(mut commands: Commands) {
     commands.trigger(PlaySoundtrack::Disable);
 }
 
-fn return_to_title_screen(mut commands: Commands, mut next_screen: ResMut<NextState<Screen>>) {
-    commands.trigger(EndLevel);
-    next_screen.set(Screen::Title);
+fn toggle_pause(state: Res<State<PlayingState>>, mut next_state: ResMut<NextState<PlayingState>>) {
+    next_state.set(match state.get() {
+        PlayingState::Running => PlayingState::Paused,
+        PlayingState::Paused => PlayingState::Running,
+    });
+}
+
+fn restart_level(mut commands: Commands) {
+    commands.trigger(RestartLevel);
+}
+
+/// Freeze everything that moves. Stopping virtual time also stops
+/// `FixedUpdate`, and with it the world rotation.
+fn pause_game(
+    mut time: ResMut<Time<Virtual>>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+    mut score: ResMut<Score>,
+) {
+    time.pause();
+    rapier_config.physics_pipeline_active = false;
+    score.stopwatch.pause();
+}
+
+/// This also runs when leaving [`Screen::Playing`] while paused.
+fn resume_game(
+    mut time: ResMut<Time<Virtual>>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+    mut score: ResMut<Score>,
+) {
+    time.unpause();
+    rapier_config.physics_pipeline_active = true;
+    score.stopwatch.unpause();
 }
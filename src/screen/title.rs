@@ -5,7 +5,7 @@ use bevy::prelude::*;
 #[cfg(feature = "dev")]
 use bevy::dev_tools::states::log_transitions;
 
-use super::Screen;
+use super::{playing::StartingLevel, Screen};
 use crate::{
     game::settings::{GameSettings, ToggleSound},
     systems::fade::{FadeCompleted, FadeIn, FadeOut},
@@ -77,14 +77,16 @@ fn on_fade_completed(
 #[reflect(Component)]
 enum TitleAction {
     Play,
+    LevelSelect,
     SoundToggle,
+    Controls,
     Credits,
     /// Exit doesn't work well with embedded applications.
     #[cfg(not(target_family = "wasm"))]
     Exit,
 }
 
-fn enter_title(mut commands: Commands) {
+fn enter_title(mut commands: Commands, settings: Res<GameSettings>) {
     commands
         .ui_root()
         .insert(StateScoped(Screen::Title))
@@ -92,8 +94,12 @@ fn enter_title(mut commands: Commands) {
             children.title("DIZZY DUCKLINGS");
             children.button("Play").insert(TitleAction::Play);
             children
-                .button("Sound: ON")
+                .button("Level Select")
+                .insert(TitleAction::LevelSelect);
+            children
+                .button(sound_label(&settings))
                 .insert((TitleAction::SoundToggle, SoundButton));
+            children.button("Controls").insert(TitleAction::Controls);
             children.button("Credits").insert(TitleAction::Credits);
 
             #[cfg(not(target_family = "wasm"))]
@@ -104,6 +110,7 @@ fn enter_title(mut commands: Commands) {
 fn handle_title_action(
     mut commands: Commands,
     mut settings: ResMut<GameSettings>,
+    mut starting_level: ResMut<StartingLevel>,
     mut next_screen: ResMut<NextState<Screen>>,
     mut next_state: ResMut<NextState<TitleState>>,
     mut button_query: InteractionQuery<&TitleAction>,
@@ -112,13 +119,16 @@ fn handle_title_action(
         if matches!(interaction, Interaction::Pressed) {
             match action {
                 TitleAction::Play => {
+                    starting_level.0 = 0;
                     next_state.set(TitleState::ActionPlayFadingOut);
                     commands.trigger(FadeOut { duration: 0.5 });
                 }
+                TitleAction::LevelSelect => next_screen.set(Screen::LevelSelect),
                 TitleAction::SoundToggle => {
                     settings.sound_enabled = !settings.sound_enabled;
                     commands.trigger(ToggleSound);
                 }
+                TitleAction::Controls => next_screen.set(Screen::Controls),
                 TitleAction::Credits => next_screen.set(Screen::Credits),
 
                 #[cfg(not(target_family = "wasm"))]
@@ -131,8 +141,10 @@ fn handle_title_action(
     }
 }
 
+/// Marks a button whose label shows whether sound is on. The label is kept
+/// up to date whenever sound is toggled.
 #[derive(Component)]
-struct SoundButton;
+pub(super) struct SoundButton;
 
 fn toggle_sound(
     _trigger: Trigger<ToggleSound>,
@@ -143,12 +155,16 @@ fn toggle_sound(
     for (children, _) in &mut query.iter_mut() {
         for child in children {
             if let Ok(mut text) = text_query.get_mut(*child) {
-                text.sections[0].value = if settings.sound_enabled {
-                    "Sound: ON".to_string()
-                } else {
-                    "Sound: OFF".to_string()
-                };
+                text.sections[0].value = sound_label(&settings).to_string();
             }
         }
     }
 }
+
+pub(super) fn sound_label(settings: &GameSettings) -> &'static str {
+    if settings.sound_enabled {
+        "Sound: ON"
+    } else {
+        "Sound: OFF"
+    }
+}
@@ -0,0 +1,228 @@
+//! Gamepad navigation for menus.
+//!
+//! The D-pad or left stick moves focus between buttons, the south button
+//! (A on Xbox) presses the focused button, and the east button (B on Xbox)
+//! presses the button marked with [`BackButton`], if there is one.
+//!
+//! Pressing works by setting [`Interaction::Pressed`] on the button, so
+//! screens handle gamepad presses exactly like mouse clicks. Screens that read
+//! the gamepad themselves can turn navigation off through [`GamepadNavigation`].
+
+use bevy::prelude::*;
+
+use super::interaction::InteractionPalette;
+use crate::{
+    game::{assets::SfxKey, audio::sfx::PlaySfx},
+    AppSet,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Focused>();
+    app.register_type::<BackButton>();
+    app.add_systems(
+        Update,
+        (
+            release_gamepad_presses,
+            (navigate_focus, press_focused).in_set(GamepadNavigation),
+            apply_focus_palette,
+        )
+            .chain()
+            .in_set(AppSet::RecordInput),
+    );
+}
+
+/// The systems that move focus and press buttons from gamepad input.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GamepadNavigation;
+
+/// The button that currently has gamepad focus.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Focused;
+
+/// Marks the button that the gamepad's back button should press.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct BackButton;
+
+/// Marks a button that was pressed with the gamepad, so that it can be
+/// released again on the next frame.
+#[derive(Component)]
+struct GamepadPressed;
+
+/// Stick input needs to pass this before it counts as a direction.
+const STICK_THRESHOLD: f32 = 0.5;
+
+type NavigableQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static GlobalTransform,
+        &'static InheritedVisibility,
+    ),
+    (With<Button>, With<InteractionPalette>),
+>;
+
+fn release_gamepad_presses(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Interaction), With<GamepadPressed>>,
+) {
+    for (entity, mut interaction) in &mut query {
+        interaction.set_if_neq(Interaction::None);
+        commands.entity(entity).remove::<GamepadPressed>();
+    }
+}
+
+fn navigate_focus(
+    mut commands: Commands,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    mut previous_stick: Local<Vec2>,
+    buttons: NavigableQuery,
+    focused: Query<Entity, With<Focused>>,
+) {
+    // Read the D-pad, and the stick when it first crosses the threshold.
+    let mut direction = Vec2::ZERO;
+    let mut stick = Vec2::ZERO;
+    for gamepad in gamepads.iter() {
+        let just_pressed =
+            |button_type| gamepad_buttons.just_pressed(GamepadButton::new(gamepad, button_type));
+        if just_pressed(GamepadButtonType::DPadUp) {
+            direction.y -= 1.0;
+        }
+        if just_pressed(GamepadButtonType::DPadDown) {
+            direction.y += 1.0;
+        }
+        if just_pressed(GamepadButtonType::DPadLeft) {
+            direction.x -= 1.0;
+        }
+        if just_pressed(GamepadButtonType::DPadRight) {
+            direction.x += 1.0;
+        }
+
+        let axis = |axis_type| {
+            gamepad_axes
+                .get(GamepadAxis::new(gamepad, axis_type))
+                .unwrap_or_default()
+        };
+        // UI coordinates have Y pointing down.
+        stick += Vec2::new(
+            axis(GamepadAxisType::LeftStickX),
+            -axis(GamepadAxisType::LeftStickY),
+        );
+    }
+
+    let stick_direction = stick_to_direction(stick);
+    if stick_direction != stick_to_direction(*previous_stick) {
+        direction += stick_direction;
+    }
+    *previous_stick = stick;
+
+    if direction == Vec2::ZERO {
+        return;
+    }
+
+    let visible = || buttons.iter().filter(|(_, _, visibility)| visibility.get());
+    let current = focused
+        .iter()
+        .find_map(|entity| buttons.get(entity).ok())
+        .filter(|(_, _, visibility)| visibility.get());
+
+    let next = match current {
+        // Nothing is focused yet, so start from the top left.
+        None => visible()
+            .min_by(|(_, a, _), (_, b, _)| {
+                let (a, b) = (a.translation(), b.translation());
+                (a.y, a.x).partial_cmp(&(b.y, b.x)).unwrap()
+            })
+            .map(|(entity, _, _)| entity),
+        // Otherwise pick the nearest button in the given direction, favouring
+        // buttons that line up with the current one.
+        Some((current, current_transform, _)) => {
+            let from = current_transform.translation().truncate();
+            let direction = direction.normalize();
+            visible()
+                .filter(|(entity, _, _)| *entity != current)
+                .filter_map(|(entity, transform, _)| {
+                    let offset = transform.translation().truncate() - from;
+                    let along = offset.dot(direction);
+                    if along <= 0.0 {
+                        return None;
+                    }
+                    let across = offset.perp_dot(direction).abs();
+                    Some((entity, along + across * 2.0))
+                })
+                .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+                .map(|(entity, _)| entity)
+        }
+    };
+
+    let Some(next) = next else {
+        return;
+    };
+    for entity in &focused {
+        commands.entity(entity).remove::<Focused>();
+    }
+    commands.entity(next).insert(Focused);
+    commands.trigger(PlaySfx::Key(SfxKey::ButtonHover));
+}
+
+fn stick_to_direction(stick: Vec2) -> Vec2 {
+    if stick.length() < STICK_THRESHOLD {
+        return Vec2::ZERO;
+    }
+    if stick.x.abs() > stick.y.abs() {
+        Vec2::new(stick.x.signum(), 0.0)
+    } else {
+        Vec2::new(0.0, stick.y.signum())
+    }
+}
+
+fn press_focused(
+    mut commands: Commands,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    mut buttons: Query<
+        (Entity, &mut Interaction, Has<Focused>, Has<BackButton>),
+        Or<(With<Focused>, With<BackButton>)>,
+    >,
+) {
+    let just_pressed = |button_type| {
+        gamepads
+            .iter()
+            .any(|gamepad| gamepad_buttons.just_pressed(GamepadButton::new(gamepad, button_type)))
+    };
+    let confirm = just_pressed(GamepadButtonType::South);
+    let back = just_pressed(GamepadButtonType::East);
+
+    for (entity, mut interaction, is_focused, is_back) in &mut buttons {
+        if (confirm && is_focused) || (back && is_back) {
+            *interaction = Interaction::Pressed;
+            commands.entity(entity).insert(GamepadPressed);
+        }
+    }
+}
+
+/// Show the focused button in its hovered colour. Buttons that lose focus go
+/// back to normal.
+fn apply_focus_palette(
+    mut focused: Query<
+        (&Interaction, &InteractionPalette, &mut BackgroundColor),
+        (With<Focused>, Or<(Added<Focused>, Changed<Interaction>)>),
+    >,
+    mut removed: RemovedComponents<Focused>,
+    mut unfocused: Query<(&InteractionPalette, &mut BackgroundColor), Without<Focused>>,
+) {
+    for (interaction, palette, mut background) in &mut focused {
+        if *interaction == Interaction::None {
+            *background = palette.hovered.into();
+        }
+    }
+    for entity in removed.read() {
+        if let Ok((palette, mut background)) = unfocused.get_mut(entity) {
+            *background = palette.none.into();
+        }
+    }
+}
@@ -2,10 +2,13 @@ use bevy::prelude::*;
 
 pub const BUTTON_HOVERED_BACKGROUND: Color = Color::linear_rgb(0.409, 0.168, 0.101);
 pub const BUTTON_PRESSED_BACKGROUND: Color = Color::linear_rgb(0.159, 0.035, 0.000);
+pub const BUTTON_DISABLED_BACKGROUND: Color = Color::linear_rgb(0.12, 0.08, 0.07);
 
 pub const TITLE_TEXT: Color = Color::srgb(0.925, 0.925, 0.5);
 pub const BUTTON_TEXT: Color = Color::srgb(0.925, 0.925, 0.925);
+pub const BUTTON_DISABLED_TEXT: Color = Color::srgb(0.5, 0.5, 0.5);
 pub const LABEL_TEXT: Color = Color::srgb(0.867, 0.827, 0.412);
 pub const HEADER_TEXT: Color = Color::srgb(0.867, 0.827, 0.412);
 
 pub const NODE_BACKGROUND: Color = Color::linear_rgb(0.290, 0.072, 0.011);
+pub const OVERLAY_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.6);
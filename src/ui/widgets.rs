@@ -18,6 +18,15 @@ pub trait Widgets {
     fn label(&mut self, text: impl Into<String>) -> EntityCommands;
 
     fn big_label(&mut self, text: impl Into<String>) -> EntityCommands;
+
+    /// Spawn a smaller button with a title and a line of details underneath.
+    /// Disabled cards are greyed out and do not change colour when hovered.
+    fn card(
+        &mut self,
+        title: impl Into<String>,
+        details: impl Into<String>,
+        enabled: bool,
+    ) -> EntityCommands;
 }
 
 impl<T: Spawn> Widgets for T {
@@ -174,6 +183,78 @@ impl<T: Spawn> Widgets for T {
         });
         entity
     }
+
+    fn card(
+        &mut self,
+        title: impl Into<String>,
+        details: impl Into<String>,
+        enabled: bool,
+    ) -> EntityCommands {
+        let (background, palette, text_color) = if enabled {
+            (
+                NODE_BACKGROUND,
+                InteractionPalette {
+                    none: NODE_BACKGROUND,
+                    hovered: BUTTON_HOVERED_BACKGROUND,
+                    pressed: BUTTON_PRESSED_BACKGROUND,
+                },
+                BUTTON_TEXT,
+            )
+        } else {
+            (
+                BUTTON_DISABLED_BACKGROUND,
+                InteractionPalette {
+                    none: BUTTON_DISABLED_BACKGROUND,
+                    hovered: BUTTON_DISABLED_BACKGROUND,
+                    pressed: BUTTON_DISABLED_BACKGROUND,
+                },
+                BUTTON_DISABLED_TEXT,
+            )
+        };
+
+        let mut entity = self.spawn((
+            Name::new("Card"),
+            ButtonBundle {
+                style: Style {
+                    width: Px(210.0),
+                    height: Px(70.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                border_radius: BorderRadius::all(Val::Px(10.0)),
+                background_color: BackgroundColor(background),
+                ..default()
+            },
+            palette,
+        ));
+        entity.with_children(|children| {
+            children.spawn((
+                Name::new("Card Title"),
+                TextBundle::from_section(
+                    title,
+                    TextStyle {
+                        font_size: 24.0,
+                        color: text_color,
+                        ..default()
+                    },
+                ),
+            ));
+            children.spawn((
+                Name::new("Card Details"),
+                TextBundle::from_section(
+                    details,
+                    TextStyle {
+                        font_size: 16.0,
+                        color: text_color,
+                        ..default()
+                    },
+                ),
+            ));
+        });
+        entity
+    }
 }
 
 /// An extension trait for spawning UI containers.